@@ -2,7 +2,11 @@
 
 #[ink::contract]
 mod simple_token {
-    use ink::storage::Mapping;
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::env::hash::Blake2x256;
+    use ink::prelude::string::String;
+    use ink::prelude::vec::Vec;
+    use ink::storage::{Lazy, Mapping, StorageVec};
 
     #[derive(scale::Encode, scale::Decode, Debug, PartialEq, Eq)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
@@ -19,6 +23,187 @@ mod simple_token {
         AddressBlacklisted,
         /// Invalid amount (e.g., zero amount)
         InvalidAmount,
+        /// Arithmetic overflow or underflow
+        Overflow,
+        /// Transfer amount is below the configured minimum
+        BelowMinimum,
+        /// Beneficiary already has a vesting schedule
+        VestingAlreadyExists,
+        /// Beneficiary has no vesting schedule
+        NoVestingSchedule,
+        /// Snapshot does not exist or has no rewards
+        InvalidSnapshot,
+        /// Rewards were already deposited for the snapshot
+        RewardsAlreadyDeposited,
+        /// Reward or claim was already taken
+        AlreadyClaimed,
+        /// Merkle proof does not match the airdrop root
+        InvalidProof,
+        /// Transfer hook call failed while it is required to succeed
+        HookFailed,
+        /// Call re-entered the contract while a cross-contract call is in progress
+        ReentrantCall,
+        /// Transfers are locked by the emergency lock
+        EmergencyLocked,
+        /// Emergency unlock was not requested or its delay has not passed
+        UnlockNotReady,
+        /// Allowance exceeds the configured maximum
+        AllowanceTooHigh,
+        /// Input vectors have different lengths
+        LengthMismatch,
+        /// Caller is not authorized for the operation
+        NotAuthorized,
+        /// Address cannot be used for the operation
+        InvalidAddress,
+        /// Memo exceeds the maximum length
+        MemoTooLong,
+        /// Sender must wait for the transfer cooldown to pass
+        CooldownActive,
+        /// Receiving contract rejected the tokens
+        ReceiverRejected,
+        /// Spender is restricted to another recipient
+        RecipientNotAllowed,
+        /// Call to another token contract failed
+        TransferFailed,
+        /// Migration already ran for this code version
+        AlreadyMigrated,
+        /// Caller is neither the owner nor holds the required role
+        MissingRole,
+        /// Mint exceeds the minter's remaining cap
+        MintCapExceeded,
+        /// Mint would exceed the maximum supply
+        CapExceeded,
+        /// Maximum supply was already set
+        MaxSupplyAlreadySet,
+        /// Batch has more than `MAX_BATCH_LEN` entries
+        BatchTooLarge,
+        /// Vesting schedule is not revocable or was already revoked
+        NotRevocable,
+        /// No token lock with this id
+        LockNotFound,
+        /// Release timestamp of the token lock has not passed
+        StillLocked,
+        /// No stream with this id
+        StreamNotFound,
+        /// No scheduled transfer with this id
+        ScheduledTransferNotFound,
+        /// Execution timestamp of the scheduled transfer has not passed
+        ScheduledTransferNotReady,
+        /// No tokens are held outside the contract to receive dividends
+        NoDividendHolders,
+        /// Historical lookup for a block that is not yet finished
+        FutureLookup,
+        /// Ownership was renounced, so no account can perform administrative actions
+        OwnershipRenounced,
+        /// Transfer amount exceeds the maximum per transfer
+        ExceedsMaxTx,
+        /// Recipient balance would exceed the maximum per wallet
+        ExceedsMaxWallet,
+        /// Replacing the contract code failed
+        UpgradeFailed,
+        /// Signature does not recover to the expected signer
+        InvalidSignature,
+        /// Nonce does not match the signer's current nonce
+        InvalidNonce,
+        /// Signed authorization is past its deadline
+        PermitExpired,
+        /// Operation must go through a confirmed multisig proposal
+        ProposalRequired,
+        /// Multisig threshold is zero or above the number of owners
+        InvalidThreshold,
+        /// No proposal with this id
+        ProposalNotFound,
+        /// Proposal was already executed
+        ProposalExecuted,
+        /// Multisig owner already confirmed the proposal
+        AlreadyConfirmed,
+        /// No queued action with this id
+        ActionNotFound,
+        /// Timelock delay of the queued action has not passed
+        ActionNotReady,
+        /// Operation must be queued with `queue_action` while the timelock delay is set
+        TimelockRequired,
+        /// Foreign token could not be called or did not answer with a PSP22 result
+        ForeignCallFailed,
+    }
+
+    /// Errors of the PSP22 standard interface
+    #[derive(scale::Encode, scale::Decode, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum PSP22Error {
+        /// Any error not covered by the standard, carrying the name of the contract error
+        Custom(String),
+        /// Not enough balance to perform the operation
+        InsufficientBalance,
+        /// Not enough allowance to perform the operation
+        InsufficientAllowance,
+        /// Recipient is the zero address
+        ZeroRecipientAddress,
+        /// Sender is the zero address
+        ZeroSenderAddress,
+        /// Receiving contract rejected the tokens
+        SafeTransferCheckFailed(String),
+    }
+
+    /// Error returned by a contract rejecting tokens in `PSP22Receiver::before_received`
+    #[derive(scale::Encode, scale::Decode, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum PSP22ReceiverError {
+        /// The receiver does not accept the tokens, with its reason
+        TransferRejected(String),
+    }
+
+    impl From<Error> for PSP22Error {
+        fn from(error: Error) -> Self {
+            match error {
+                Error::InsufficientBalance => PSP22Error::InsufficientBalance,
+                Error::InsufficientAllowance => PSP22Error::InsufficientAllowance,
+                Error::ReceiverRejected => PSP22Error::SafeTransferCheckFailed(String::from("ReceiverRejected")),
+                other => PSP22Error::Custom(ink::prelude::format!("{:?}", other)),
+            }
+        }
+    }
+
+    /// The PSP22 fungible token standard, with the standard message selectors
+    #[ink::trait_definition]
+    pub trait PSP22 {
+        /// Get the total token supply
+        #[ink(message)]
+        fn total_supply(&self) -> Balance;
+
+        /// Get the balance of an account
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> Balance;
+
+        /// Get the amount `spender` may still transfer on behalf of `owner`
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance;
+
+        /// Transfer `value` tokens from the caller to `to`
+        #[ink(message)]
+        fn transfer(&mut self, to: AccountId, value: Balance, data: Vec<u8>) -> Result<(), PSP22Error>;
+
+        /// Transfer `value` tokens from `from` to `to` using the caller's allowance
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+            data: Vec<u8>,
+        ) -> Result<(), PSP22Error>;
+
+        /// Set the allowance of `spender` over the caller's tokens to `value`
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: Balance) -> Result<(), PSP22Error>;
+
+        /// Raise the allowance of `spender` over the caller's tokens by `delta_value`
+        #[ink(message)]
+        fn increase_allowance(&mut self, spender: AccountId, delta_value: Balance) -> Result<(), PSP22Error>;
+
+        /// Lower the allowance of `spender` over the caller's tokens by `delta_value`
+        #[ink(message)]
+        fn decrease_allowance(&mut self, spender: AccountId, delta_value: Balance) -> Result<(), PSP22Error>;
     }
 
     #[ink(event)]
@@ -28,324 +213,6374 @@ mod simple_token {
         #[ink(topic)]
         to: Option<AccountId>,
         value: Balance,
+        seq: u64,
+    }
+
+    #[ink(event)]
+    pub struct LargeTransfer {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        value: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Mint {
+        #[ink(topic)]
+        to: AccountId,
+        value: Balance,
+        at: u64,
+        seq: u64,
+    }
+
+    #[ink(event)]
+    pub struct Burn {
+        #[ink(topic)]
+        from: AccountId,
+        value: Balance,
+        seq: u64,
+    }
+
+    #[ink(event)]
+    pub struct ForcedTransfer {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        value: Balance,
+        by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Paused {
+        #[ink(topic)]
+        by: AccountId,
+        until: Option<u64>,
+    }
+
+    #[ink(event)]
+    pub struct Unpaused {
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Blacklisted {
+        #[ink(topic)]
+        account: AccountId,
+        by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Unblacklisted {
+        #[ink(topic)]
+        account: AccountId,
+        by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct AllowanceForceRevoked {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        spender: AccountId,
+        by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct AdminBurn {
+        #[ink(topic)]
+        from: AccountId,
+        value: Balance,
+    }
+
+    #[ink(event)]
+    pub struct AirdropClaimed {
+        #[ink(topic)]
+        account: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct VestingRevoked {
+        #[ink(topic)]
+        beneficiary: AccountId,
+        returned: Balance,
+    }
+
+    #[ink(event)]
+    pub struct TokensLocked {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        beneficiary: AccountId,
+        amount: Balance,
+        unlock_at: u64,
+    }
+
+    #[ink(event)]
+    pub struct LockReleased {
+        #[ink(topic)]
+        id: u32,
+    }
+
+    #[ink(event)]
+    pub struct StreamCreated {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        sender: AccountId,
+        #[ink(topic)]
+        recipient: AccountId,
+        deposit: Balance,
+        start: u64,
+        stop: u64,
+    }
+
+    #[ink(event)]
+    pub struct StreamWithdrawn {
+        #[ink(topic)]
+        id: u32,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct StreamCancelled {
+        #[ink(topic)]
+        id: u32,
+        recipient_amount: Balance,
+        sender_amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct TransferScheduled {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+        execute_after: u64,
+    }
+
+    #[ink(event)]
+    pub struct ScheduledTransferExecuted {
+        #[ink(topic)]
+        id: u32,
+    }
+
+    #[ink(event)]
+    pub struct ScheduledTransferCancelled {
+        #[ink(topic)]
+        id: u32,
+    }
+
+    #[ink(event)]
+    pub struct Staked {
+        #[ink(topic)]
+        account: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Unstaked {
+        #[ink(topic)]
+        account: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct RewardPaid {
+        #[ink(topic)]
+        account: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct DividendsDistributed {
+        #[ink(topic)]
+        by: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct DividendWithdrawn {
+        #[ink(topic)]
+        account: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct DelegateChanged {
+        #[ink(topic)]
+        delegator: AccountId,
+        #[ink(topic)]
+        from_delegate: Option<AccountId>,
+        #[ink(topic)]
+        to_delegate: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct DelegateVotesChanged {
+        #[ink(topic)]
+        delegate: AccountId,
+        previous_votes: Balance,
+        new_votes: Balance,
+    }
+
+    #[ink(event)]
+    pub struct FeeCharged {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        collector: AccountId,
+        amount: Balance,
+    }
+
+    #[ink(event)]
+    pub struct Snapshot {
+        id: u32,
+    }
+
+    #[ink(event)]
+    pub struct TransferWithMemo {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        value: Balance,
+        memo: Vec<u8>,
+    }
+
+    #[ink(event)]
+    pub struct OwnershipTransferred {
+        #[ink(topic)]
+        previous: AccountId,
+        #[ink(topic)]
+        new: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct RoleGranted {
+        #[ink(topic)]
+        role: Role,
+        #[ink(topic)]
+        account: AccountId,
+        by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct RoleRevoked {
+        #[ink(topic)]
+        role: Role,
+        #[ink(topic)]
+        account: AccountId,
+        by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct Upgraded {
+        code_hash: Hash,
+    }
+
+    #[ink(event)]
+    pub struct ActionQueued {
+        #[ink(topic)]
+        id: u32,
+        action: PendingAction,
+        execute_after: u64,
+    }
+
+    #[ink(event)]
+    pub struct ActionExecuted {
+        #[ink(topic)]
+        id: u32,
+    }
+
+    #[ink(event)]
+    pub struct ActionCancelled {
+        #[ink(topic)]
+        id: u32,
+    }
+
+    #[ink(event)]
+    pub struct ProposalCreated {
+        #[ink(topic)]
+        id: u32,
+        action: Action,
+        proposer: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct ProposalConfirmed {
+        #[ink(topic)]
+        id: u32,
+        #[ink(topic)]
+        by: AccountId,
+    }
+
+    #[ink(event)]
+    pub struct ProposalExecuted {
+        #[ink(topic)]
+        id: u32,
+    }
+
+    #[ink(event)]
+    pub struct Approval {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        spender: AccountId,
+        value: Balance,
+    }
+
+    /// Linear vesting schedule for a beneficiary
+    #[derive(scale::Encode, scale::Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct VestingSchedule {
+        /// Total amount escrowed for the beneficiary
+        pub total: Balance,
+        /// Amount already released to the beneficiary
+        pub released: Balance,
+        /// Timestamp at which vesting starts
+        pub start: u64,
+        /// Time after `start` before which nothing vests
+        pub cliff: u64,
+        /// Length of the vesting period
+        pub duration: u64,
+        /// Whether the owner may revoke the unvested remainder
+        pub revocable: bool,
+        /// Whether the schedule was revoked, `total` then only covers what had vested
+        pub revoked: bool,
+    }
+
+    /// Tokens held by the contract for a beneficiary until a release timestamp
+    #[derive(scale::Encode, scale::Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct TokenLock {
+        /// Account that receives the tokens on release
+        pub beneficiary: AccountId,
+        /// Amount held
+        pub amount: Balance,
+        /// Timestamp from which the lock can be released
+        pub unlock_at: u64,
+    }
+
+    /// Deposit streamed linearly from a sender to a recipient between `start` and `stop`
+    #[derive(scale::Encode, scale::Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Stream {
+        /// Account that funded the stream
+        pub sender: AccountId,
+        /// Account the stream pays out to
+        pub recipient: AccountId,
+        /// Total amount streamed
+        pub deposit: Balance,
+        /// Timestamp at which streaming starts
+        pub start: u64,
+        /// Timestamp at which the whole deposit has streamed
+        pub stop: u64,
+        /// Amount already withdrawn by the recipient
+        pub withdrawn: Balance,
+    }
+
+    /// Transfer escrowed by the contract until it can be executed
+    #[derive(scale::Encode, scale::Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct ScheduledTransfer {
+        /// Account that scheduled and funded the transfer
+        pub from: AccountId,
+        /// Recipient of the transfer
+        pub to: AccountId,
+        /// Amount escrowed
+        pub amount: Balance,
+        /// Timestamp from which the transfer can be executed
+        pub execute_after: u64,
     }
 
-    #[ink(event)]
-    pub struct Mint {
-        #[ink(topic)]
-        to: AccountId,
-        value: Balance,
-    }
+    /// Directions in which a blacklisted address is blocked
+    #[derive(scale::Encode, scale::Decode, Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct BlacklistMode {
+        /// Address cannot send tokens
+        pub block_send: bool,
+        /// Address cannot receive tokens
+        pub block_receive: bool,
+    }
+
+    impl Default for BlacklistMode {
+        fn default() -> Self {
+            Self {
+                block_send: true,
+                block_receive: true,
+            }
+        }
+    }
+
+    /// Administrative action executed through the timelock
+    #[derive(scale::Encode, scale::Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum PendingAction {
+        /// Mint `amount` tokens to `to`
+        Mint { to: AccountId, amount: Balance },
+        /// Send `basis_points` of every transfer to `collector`, as `set_fee`
+        SetFee { basis_points: u16, collector: AccountId },
+        /// Change the timelock delay, as `set_timelock_delay_secs`
+        SetTimelockDelay { delay_secs: u64 },
+    }
+
+    /// Action waiting in the timelock queue
+    #[derive(scale::Encode, scale::Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct QueuedAction {
+        /// Action to execute
+        pub action: PendingAction,
+        /// Timestamp from which the action can be executed
+        pub execute_after: u64,
+    }
+
+    /// Administrative action executed through a multisig proposal
+    #[derive(scale::Encode, scale::Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum Action {
+        /// Mint `amount` tokens to `to`
+        Mint { to: AccountId, amount: Balance },
+        /// Pause all token transfers
+        Pause,
+        /// Resume token transfers
+        Unpause,
+    }
+
+    /// Multisig proposal and its confirmation progress
+    #[derive(scale::Encode, scale::Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct Proposal {
+        /// Action executed once the threshold is reached
+        pub action: Action,
+        /// Number of distinct owners that confirmed
+        pub confirmations: u8,
+        /// Whether the action was executed
+        pub executed: bool,
+    }
+
+    /// Permission that can be granted to accounts besides the owner, who implicitly holds every role
+    #[derive(scale::Encode, scale::Decode, Debug, Clone, Copy, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum Role {
+        /// May grant and revoke roles
+        Admin,
+        /// May mint tokens
+        Minter,
+        /// May pause and unpause transfers
+        Pauser,
+        /// May manage the blacklist
+        Blacklister,
+    }
+
+    /// How fractional transfer tax amounts are rounded
+    #[derive(scale::Encode, scale::Decode, Debug, Clone, Copy, PartialEq, Eq, Default)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub enum RoundingMode {
+        /// Round towards zero
+        #[default]
+        Down,
+        /// Round away from zero
+        Up,
+        /// Round to the nearest unit, halves up
+        Nearest,
+    }
+
+    /// Transfer tax credited to a treasury and a reward pool
+    #[derive(scale::Encode, scale::Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(
+        feature = "std",
+        derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout)
+    )]
+    pub struct TaxDistribution {
+        /// Share of every transfer sent to `treasury`, in basis points
+        pub treasury_bps: u16,
+        /// Share of every transfer sent to `reward`, in basis points
+        pub reward_bps: u16,
+        /// Treasury account
+        pub treasury: AccountId,
+        /// Reward pool account
+        pub reward: AccountId,
+    }
+
+    /// Supply counters read together, `total_minted - total_burned == total_supply`
+    #[derive(scale::Encode, scale::Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct SupplyStats {
+        /// Tokens currently in existence
+        pub total_supply: Balance,
+        /// Cumulative amount of tokens ever minted
+        pub total_minted: Balance,
+        /// Cumulative amount of tokens ever burned
+        pub total_burned: Balance,
+    }
+
+    /// Outcome `validate_batch` predicts for one recipient
+    #[derive(scale::Encode, scale::Decode, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum BatchEntryStatus {
+        /// Recipient can be credited
+        Ok,
+        /// Recipient is blacklisted from receiving
+        Blacklisted,
+        /// Caller's tokens cannot move: the contract is paused or locked, or the caller is blocked from sending
+        Frozen,
+        /// Recipient is the zero address
+        ZeroAddress,
+        /// Caller's balance runs out before this entry
+        InsufficientBalance,
+        /// Any other check of `can_transfer` fails
+        Rejected(Error),
+    }
+
+    /// Summary of a successful `batch_transfer_receipt`
+    #[derive(scale::Encode, scale::Decode, Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct BatchReceipt {
+        /// Total amount debited from the caller
+        pub total_sent: Balance,
+        /// Number of recipients credited
+        pub recipient_count: u32,
+    }
+
+    /// Version of the contract code
+    pub const CONTRACT_VERSION: u32 = 1;
+
+    /// Version of the storage layout written by this code
+    pub const STORAGE_VERSION: u32 = 1;
+
+    /// Number of decimals used by `new`
+    pub const DEFAULT_DECIMALS: u8 = 18;
+
+    /// Maximum length of a transfer memo in bytes
+    pub const MAX_MEMO_LEN: usize = 256;
+
+    /// Maximum number of entries in a batch with per-recipient amounts
+    pub const MAX_BATCH_LEN: usize = 100;
+
+    /// Scale of the accumulated staking reward per staked token
+    pub const REWARD_PRECISION: Balance = 1_000_000_000;
+
+    /// Storage for allowances: (owner, spender) -> amount
+    type Allowance = (AccountId, AccountId);
+
+    /// Balance recorded for a snapshot: (snapshot id, balance)
+    type SnapshotBalance = (u32, Balance);
+
+    /// Amount recorded from a block onwards: (block number, amount)
+    type Checkpoint = (u32, Balance);
+
+    #[ink(storage)]
+    pub struct SimpleToken {
+        /// Mapping from account to balance
+        balances: Mapping<AccountId, Balance>,
+        /// Contract owner
+        owner: AccountId,
+        /// Total token supply
+        total_supply: Balance,
+        /// Allowances for token transfers
+        allowances: Mapping<Allowance, Balance>,
+        /// Whether the contract is paused
+        paused: bool,
+        /// Blacklisted addresses
+        blacklist: Mapping<AccountId, BlacklistMode>,
+        /// Whether approvals are also blocked while the contract is paused
+        pause_approvals: bool,
+        /// Spenders holding a nonzero allowance, per owner
+        spenders: Mapping<AccountId, Vec<AccountId>>,
+        /// Minimum amount allowed for a single transfer
+        min_transfer: Balance,
+        /// Vesting schedules, escrowed in the contract's own balance
+        vesting: Mapping<AccountId, VestingSchedule>,
+        /// Id of the latest snapshot, 0 if none was taken
+        current_snapshot_id: u32,
+        /// Balances recorded lazily before the first change after each snapshot
+        account_snapshots: Mapping<AccountId, Vec<SnapshotBalance>>,
+        /// Total supply recorded when each snapshot was taken
+        supply_snapshots: Mapping<u32, Balance>,
+        /// Reward pool deposited for each snapshot, escrowed in the contract's own balance
+        rewards: Mapping<u32, Balance>,
+        /// Whether an account claimed its reward for a snapshot
+        claimed: Mapping<(AccountId, u32), bool>,
+        /// Contract notified via `on_token_transfer` after each transfer
+        transfer_hook: Option<AccountId>,
+        /// Whether a failing transfer hook reverts the transfer
+        hook_must_succeed: bool,
+        /// Reentrancy guard, kept in its own storage cell so it is visible to re-entrant calls
+        locked: Lazy<bool>,
+        /// Set while `transfer_batch` runs, so the cooldown only applies to its first entry
+        batching: Lazy<bool>,
+        /// Whether all transfers are locked by the emergency lock
+        emergency_locked: bool,
+        /// Timestamp after which a requested emergency unlock can be executed
+        emergency_unlock_at: Option<u64>,
+        /// Delay between requesting and executing an emergency unlock, in seconds
+        emergency_delay_secs: u64,
+        /// Version of the storage layout, used by migrations to detect old layouts
+        storage_version: u32,
+        /// Maximum allowance a single spender may be granted, unlimited if `None`
+        max_allowance: Option<Balance>,
+        /// Number of decimals of one whole token
+        decimals: u8,
+        /// Bridge contracts allowed to mint and burn wrapped tokens
+        bridges: Mapping<AccountId, bool>,
+        /// Cumulative amount of tokens ever minted
+        total_minted: Balance,
+        /// Cumulative amount of tokens ever burned
+        total_burned: Balance,
+        /// Minimum time between two transfers from the same account, in seconds
+        transfer_cooldown_secs: u64,
+        /// Timestamp of the last transfer sent by each account
+        last_transfer_at: Mapping<AccountId, u64>,
+        /// Maximum amount of a single transfer, unlimited if `None`
+        max_tx_amount: Option<Balance>,
+        /// Maximum balance a single account may reach through transfers, unlimited if `None`
+        max_wallet: Option<Balance>,
+        /// Delay between queuing and executing an administrative action, in seconds
+        timelock_delay_secs: u64,
+        /// Id assigned to the next queued action
+        next_action_id: u32,
+        /// Actions waiting in the timelock queue
+        queued_actions: Mapping<u32, QueuedAction>,
+        /// Whether batch credits to blocked recipients are escrowed instead of reverting
+        escrow_blocked_transfers: bool,
+        /// Tokens escrowed for blocked recipients, held in the contract's own balance
+        pending_withdrawals: Mapping<AccountId, Balance>,
+        /// Next nonce expected in a signed authorization of each account
+        nonces: Mapping<AccountId, u64>,
+        /// Merkle root of the `(account, amount)` airdrop allocations, claims are closed while zero
+        merkle_root: [u8; 32],
+        /// Whether an account claimed its airdrop allocation
+        claimed_airdrop: Mapping<AccountId, bool>,
+        /// Accounts whose balances do not count towards the circulating supply
+        excluded_from_circulating: Mapping<AccountId, bool>,
+        /// Sum of the balances of all excluded accounts
+        excluded_balance_sum: Balance,
+        /// Owners allowed to propose and confirm multisig actions
+        multisig_owners: Mapping<AccountId, bool>,
+        /// Confirmations required to execute a proposal, 0 when multisig is disabled
+        threshold: u8,
+        /// Id assigned to the next proposal
+        next_proposal_id: u32,
+        /// Multisig proposals
+        proposals: Mapping<u32, Proposal>,
+        /// Whether a multisig owner confirmed a proposal
+        confirmations: Mapping<(u32, AccountId), bool>,
+        /// Transfers above this amount also emit `LargeTransfer`, 0 disables it
+        large_transfer_threshold: Balance,
+        /// Only recipient a spender may send an owner's tokens to, when restricted
+        allowed_recipients: Mapping<Allowance, AccountId>,
+        /// Accounts with a nonzero balance, in no particular order
+        holders: StorageVec<AccountId>,
+        /// Position of each holder in `holders`
+        holder_index: Mapping<AccountId, u32>,
+        /// Share of every `transfer`/`transfer_from` burned from the sender, in basis points
+        burn_bps: u16,
+        /// End of a timed pause, `None` while the pause is indefinite
+        pause_until_ts: Option<u64>,
+        /// Timestamp of the last mint to each account
+        last_mint_at: Mapping<AccountId, u64>,
+        /// Treasury and reward pool shares of the transfer tax, the burn share is `burn_bps`
+        tax_distribution: Option<TaxDistribution>,
+        /// Rounding applied to every transfer tax share
+        fee_rounding: RoundingMode,
+        /// Contract deciding which transfers are allowed in place of the local blacklist
+        compliance_registry: Option<AccountId>,
+        /// Whether `migrate` ran since the last upgrade
+        migrated: bool,
+        /// Sequence number of the last `Transfer`, `Mint` or `Burn` event
+        event_seq: u64,
+        /// Token name
+        name: Option<String>,
+        /// Token symbol
+        symbol: Option<String>,
+        /// Account nominated to accept ownership
+        pending_owner: Option<AccountId>,
+        /// Roles granted to accounts besides the owner
+        roles: Mapping<(Role, AccountId), bool>,
+        /// Remaining amount each capped minter may mint
+        minter_caps: Mapping<AccountId, Balance>,
+        /// Maximum total supply, set at most once
+        max_supply: Option<Balance>,
+        /// Id assigned to the next token lock
+        next_lock_id: u32,
+        /// Token locks by id
+        locks: Mapping<u32, TokenLock>,
+        /// Ids of the pending locks of each beneficiary
+        account_locks: Mapping<AccountId, Vec<u32>>,
+        /// Id assigned to the next stream
+        next_stream_id: u32,
+        /// Open streams by id
+        streams: Mapping<u32, Stream>,
+        /// Id assigned to the next scheduled transfer
+        next_scheduled_id: u32,
+        /// Pending scheduled transfers by id
+        scheduled_transfers: Mapping<u32, ScheduledTransfer>,
+        /// Staked amount per account
+        staked: Mapping<AccountId, Balance>,
+        /// Sum of all staked amounts
+        total_staked: Balance,
+        /// Staking reward paid out per block
+        reward_rate: Balance,
+        /// Staking rewards funded but not yet paid out
+        reward_pool: Balance,
+        /// Accumulated reward per staked token, scaled by `REWARD_PRECISION`
+        reward_per_token: Balance,
+        /// Block up to which staking rewards were accrued
+        last_reward_block: u32,
+        /// `reward_per_token` at each staker's last checkpoint
+        reward_per_token_paid: Mapping<AccountId, Balance>,
+        /// Staking rewards earned up to each staker's last checkpoint
+        staking_rewards: Mapping<AccountId, Balance>,
+        /// Accumulated dividend per token held, scaled by `REWARD_PRECISION`
+        magnified_dividend_per_share: Balance,
+        /// Per-account offsets keeping accrued dividends fixed across balance changes
+        dividend_corrections: Mapping<AccountId, i128>,
+        /// Dividends withdrawn per account
+        withdrawn_dividends: Mapping<AccountId, Balance>,
+        /// Deposited dividends left over from rounding, added to the next deposit
+        undistributed_dividends: Balance,
+        /// Delegatee chosen by each account for its votes
+        delegates: Mapping<AccountId, AccountId>,
+        /// Voting power history per delegatee
+        vote_checkpoints: Mapping<AccountId, Vec<Checkpoint>>,
+        /// Balance history per account
+        balance_checkpoints: Mapping<AccountId, Vec<Checkpoint>>,
+        /// Total supply history
+        supply_checkpoints: StorageVec<Checkpoint>,
+    }
+
+    /// Zero-supply token owned by the caller, the same as `new`, for tests and tooling
+    impl Default for SimpleToken {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl SimpleToken {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            let caller = Self::env().caller();
+            Self {
+                balances: Mapping::default(),
+                owner: caller,
+                total_supply: 0,
+                allowances: Mapping::default(),
+                paused: false,
+                blacklist: Mapping::default(),
+                pause_approvals: false,
+                spenders: Mapping::default(),
+                min_transfer: 0,
+                vesting: Mapping::default(),
+                current_snapshot_id: 0,
+                account_snapshots: Mapping::default(),
+                supply_snapshots: Mapping::default(),
+                rewards: Mapping::default(),
+                claimed: Mapping::default(),
+                transfer_hook: None,
+                hook_must_succeed: false,
+                locked: Lazy::default(),
+                batching: Lazy::default(),
+                emergency_locked: false,
+                emergency_unlock_at: None,
+                emergency_delay_secs: 0,
+                storage_version: STORAGE_VERSION,
+                max_allowance: None,
+                decimals: DEFAULT_DECIMALS,
+                bridges: Mapping::default(),
+                total_minted: 0,
+                total_burned: 0,
+                transfer_cooldown_secs: 0,
+                last_transfer_at: Mapping::default(),
+                max_tx_amount: None,
+                max_wallet: None,
+                timelock_delay_secs: 0,
+                next_action_id: 0,
+                queued_actions: Mapping::default(),
+                escrow_blocked_transfers: false,
+                pending_withdrawals: Mapping::default(),
+                nonces: Mapping::default(),
+                merkle_root: [0u8; 32],
+                claimed_airdrop: Mapping::default(),
+                excluded_from_circulating: Mapping::default(),
+                excluded_balance_sum: 0,
+                multisig_owners: Mapping::default(),
+                threshold: 0,
+                next_proposal_id: 0,
+                proposals: Mapping::default(),
+                confirmations: Mapping::default(),
+                large_transfer_threshold: 0,
+                allowed_recipients: Mapping::default(),
+                holders: StorageVec::new(),
+                holder_index: Mapping::default(),
+                burn_bps: 0,
+                pause_until_ts: None,
+                last_mint_at: Mapping::default(),
+                tax_distribution: None,
+                fee_rounding: RoundingMode::Down,
+                compliance_registry: None,
+                migrated: false,
+                event_seq: 0,
+                name: None,
+                symbol: None,
+                pending_owner: None,
+                roles: Mapping::default(),
+                minter_caps: Mapping::default(),
+                max_supply: None,
+                next_lock_id: 0,
+                locks: Mapping::default(),
+                account_locks: Mapping::default(),
+                next_stream_id: 0,
+                streams: Mapping::default(),
+                next_scheduled_id: 0,
+                scheduled_transfers: Mapping::default(),
+                staked: Mapping::default(),
+                total_staked: 0,
+                reward_rate: 0,
+                reward_pool: 0,
+                reward_per_token: 0,
+                last_reward_block: 0,
+                reward_per_token_paid: Mapping::default(),
+                staking_rewards: Mapping::default(),
+                magnified_dividend_per_share: 0,
+                dividend_corrections: Mapping::default(),
+                withdrawn_dividends: Mapping::default(),
+                undistributed_dividends: 0,
+                delegates: Mapping::default(),
+                vote_checkpoints: Mapping::default(),
+                balance_checkpoints: Mapping::default(),
+                supply_checkpoints: StorageVec::new(),
+            }
+        }
+
+        /// Creates a token whose mint and pause can only be executed through
+        /// proposals confirmed by `threshold` distinct `owners`
+        #[ink(constructor)]
+        pub fn new_multisig(owners: Vec<AccountId>, threshold: u8) -> Result<Self, Error> {
+            let mut instance = Self::new();
+            let mut distinct_owners = 0;
+            for owner in owners {
+                if !instance.is_multisig_owner(owner) {
+                    instance.multisig_owners.insert(owner, &true);
+                    distinct_owners += 1;
+                }
+            }
+
+            if threshold == 0 || threshold as usize > distinct_owners {
+                return Err(Error::InvalidThreshold);
+            }
+            instance.threshold = threshold;
+            Ok(instance)
+        }
+
+        /// Creates a token owned by `owner` with its metadata set and
+        /// `initial_supply` minted to `owner`, the zero address is rejected
+        #[ink(constructor)]
+        pub fn new_with_config(
+            name: Option<String>,
+            symbol: Option<String>,
+            decimals: u8,
+            initial_supply: Balance,
+            owner: AccountId,
+        ) -> Result<Self, Error> {
+            if owner == AccountId::from([0u8; 32]) {
+                return Err(Error::InvalidAddress);
+            }
+
+            let mut instance = Self::new();
+            instance.owner = owner;
+            instance.name = name;
+            instance.symbol = symbol;
+            instance.decimals = decimals;
+            if initial_supply > 0 {
+                instance.mint_tokens(owner, initial_supply)?;
+            }
+            Ok(instance)
+        }
+
+        #[ink(message)]
+        pub fn mint(&mut self, to: AccountId, amount: Balance) -> Result<(), Error> {
+            self.ensure_direct_role(Role::Minter)?;
+            self.ensure_not_timelocked()?;
+            self.mint_as(self.env().caller(), to, amount)
+        }
+
+        /// Mint like `mint`, but a contract recipient must accept the tokens (only minters)
+        /// Contracts are called with `on_token_received(operator, value)` and must return its selector
+        #[ink(message)]
+        pub fn safe_mint(&mut self, to: AccountId, amount: Balance) -> Result<(), Error> {
+            self.ensure_direct_role(Role::Minter)?;
+            self.ensure_not_timelocked()?;
+            self.mint_as(self.env().caller(), to, amount)?;
+
+            if self.env().code_hash(&to).is_ok() {
+                self.call_token_received(self.env().caller(), to, amount)?;
+            }
+            Ok(())
+        }
+
+        /// Limit how much more a minter may mint, `None` removes the limit (only owner or admins)
+        #[ink(message)]
+        pub fn set_minter_cap(&mut self, minter: AccountId, cap: Option<Balance>) -> Result<(), Error> {
+            self.ensure_role(Role::Admin)?;
+            match cap {
+                Some(cap) => {
+                    self.minter_caps.insert(minter, &cap);
+                }
+                None => self.minter_caps.remove(minter),
+            }
+            Ok(())
+        }
+
+        /// Get how much more a minter may mint, unlimited if `None`
+        #[ink(message)]
+        pub fn minter_cap(&self, minter: AccountId) -> Option<Balance> {
+            self.minter_caps.get(minter)
+        }
+
+        /// Mint whole tokens, scaled to base units by the token decimals (only owner)
+        #[ink(message)]
+        pub fn mint_whole(&mut self, to: AccountId, whole_tokens: Balance) -> Result<(), Error> {
+            let amount = 10u128
+                .checked_pow(self.decimals as u32)
+                .and_then(|unit| whole_tokens.checked_mul(unit))
+                .ok_or(Error::Overflow)?;
+            self.mint(to, amount)
+        }
+
+        /// Get the number of decimals of one whole token
+        #[ink(message)]
+        pub fn decimals(&self) -> u8 {
+            self.decimals
+        }
+
+        /// Get the token name
+        #[ink(message)]
+        pub fn token_name(&self) -> Option<String> {
+            self.name.clone()
+        }
+
+        /// Get the token symbol
+        #[ink(message)]
+        pub fn token_symbol(&self) -> Option<String> {
+            self.symbol.clone()
+        }
+
+        /// Get the timestamp at which an account last received minted tokens
+        #[ink(message)]
+        pub fn last_mint_at(&self, account: AccountId) -> Option<u64> {
+            self.last_mint_at.get(account)
+        }
+
+        /// Get the balances of several accounts, in the same order as the input
+        #[ink(message)]
+        pub fn balances_of(&self, accounts: Vec<AccountId>) -> Vec<Balance> {
+            accounts
+                .into_iter()
+                .map(|account| self.balance_of(account))
+                .collect()
+        }
+
+        /// Get up to `len` holders starting at index `start`
+        /// Indexes are not stable: removing a holder moves the last one into its slot
+        #[ink(message)]
+        pub fn holders_page(&self, start: u32, len: u32) -> Vec<AccountId> {
+            let end = start.saturating_add(len).min(self.holders.len());
+            (start..end).filter_map(|index| self.holders.get(index)).collect()
+        }
+
+        /// Get up to `limit` holders with their balances starting at index `offset`
+        #[ink(message)]
+        pub fn holders(&self, offset: u32, limit: u32) -> Vec<(AccountId, Balance)> {
+            self.holders_page(offset, limit)
+                .into_iter()
+                .map(|account| (account, self.balance_of(account)))
+                .collect()
+        }
+
+        /// Get the holder at an index of the holders registry
+        #[ink(message)]
+        pub fn holder_at(&self, index: u32) -> Option<AccountId> {
+            self.holders.get(index)
+        }
+
+        /// Get the number of accounts with a nonzero balance
+        #[ink(message)]
+        pub fn holder_count(&self) -> u32 {
+            self.holders.len()
+        }
+
+        /// Transfer tokens with a reference attached for reconciliation, at most `MAX_MEMO_LEN` bytes
+        #[ink(message)]
+        pub fn transfer_with_memo(
+            &mut self,
+            to: AccountId,
+            amount: Balance,
+            memo: Vec<u8>,
+        ) -> Result<(), Error> {
+            if memo.len() > MAX_MEMO_LEN {
+                return Err(Error::MemoTooLong);
+            }
+
+            let caller = self.env().caller();
+            self.do_transfer(caller, to, amount)?;
+
+            self.env().emit_event(TransferWithMemo {
+                from: caller,
+                to,
+                value: amount,
+                memo,
+            });
+            Ok(())
+        }
+
+        /// Transfer tokens to a contract and notify it through `on_transfer_received(operator, from, value, data)`
+        /// The receiver must return the `on_transfer_received` selector to accept the tokens
+        #[ink(message)]
+        pub fn transfer_and_call(
+            &mut self,
+            to: AccountId,
+            amount: Balance,
+            data: Vec<u8>,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+            self.do_transfer(caller, to, amount)?;
+            self.call_transfer_receiver(caller, caller, to, amount, data)
+        }
+
+        /// Transfer tokens and, when `to` is a contract, let it accept or reject them through
+        /// the standard `PSP22Receiver::before_received(operator, from, value, data)` hook
+        /// Plain accounts are credited like with `transfer`
+        #[ink(message)]
+        pub fn transfer_to_contract(
+            &mut self,
+            to: AccountId,
+            value: Balance,
+            data: Vec<u8>,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+            self.do_transfer(caller, to, value)?;
+
+            if self.env().code_hash(&to).is_ok() {
+                self.call_psp22_receiver(caller, caller, to, value, data)?;
+            }
+            Ok(())
+        }
+
+        /// Relay a transfer authorized off-chain by `from` with an ECDSA signature
+        /// The signature covers the Blake2x256 hash of the SCALE encoded
+        /// `(contract, from, to, amount, nonce, deadline)` tuple
+        #[ink(message)]
+        pub fn transfer_with_signature(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            amount: Balance,
+            nonce: u64,
+            deadline: u64,
+            signature: [u8; 65],
+        ) -> Result<(), Error> {
+            if self.env().block_timestamp() > deadline {
+                return Err(Error::PermitExpired);
+            }
+
+            let payload = (self.env().account_id(), from, to, amount, nonce, deadline);
+            if self.recover_signer(&payload, &signature)? != from {
+                return Err(Error::InvalidSignature);
+            }
+            self.use_nonce(from, nonce)?;
+
+            self.do_transfer(from, to, amount)
+        }
+
+        /// Set an allowance authorized off-chain by `owner` with an ECDSA signature
+        /// The signature covers the Blake2x256 hash of the SCALE encoded
+        /// `(contract, b"permit", owner, spender, value, nonce, deadline)` tuple,
+        /// where `nonce` is the owner's current `nonces` value
+        #[ink(message)]
+        pub fn permit(
+            &mut self,
+            owner: AccountId,
+            spender: AccountId,
+            value: Balance,
+            deadline: u64,
+            signature: [u8; 65],
+        ) -> Result<(), Error> {
+            if self.env().block_timestamp() > deadline {
+                return Err(Error::PermitExpired);
+            }
+
+            let nonce = self.nonces(owner);
+            let payload = (self.env().account_id(), *b"permit", owner, spender, value, nonce, deadline);
+            if self.recover_signer(&payload, &signature)? != owner {
+                return Err(Error::InvalidSignature);
+            }
+            self.use_nonce(owner, nonce)?;
+
+            self.approve_spender(owner, spender, value)
+        }
+
+        /// Get the next nonce expected in a signed authorization of an account
+        #[ink(message)]
+        pub fn nonces(&self, owner: AccountId) -> u64 {
+            self.nonces.get(owner).unwrap_or(0)
+        }
+
+        /// Check whether `from` could transfer `amount` to `to` without changing any state
+        /// Runs the same validations as `transfer`, allowances are not considered
+        #[ink(message)]
+        pub fn can_transfer(&self, from: AccountId, to: AccountId, amount: Balance) -> Result<(), Error> {
+            if self.is_locked() {
+                return Err(Error::ReentrantCall);
+            }
+
+            self.ensure_not_halted()?;
+
+            let allowed = match self.compliance_registry {
+                Some(registry) => self.registry_allows(registry, from, to),
+                None => !self.is_send_blocked(from) && !self.is_receive_blocked(to),
+            };
+            if !allowed {
+                return Err(Error::AddressBlacklisted);
+            }
+
+            if amount == 0 {
+                return Err(Error::InvalidAmount);
+            }
+
+            if amount < self.min_transfer {
+                return Err(Error::BelowMinimum);
+            }
+
+            if self.balance_of(from) < amount {
+                return Err(Error::InsufficientBalance);
+            }
+
+            if self.is_cooling_down(from) {
+                return Err(Error::CooldownActive);
+            }
+
+            // Transfer limits do not apply to the owner or to tokens escrowed by the contract itself
+            if from != self.owner && to != self.owner && to != self.env().account_id() {
+                if self.max_tx_amount.is_some_and(|max| amount > max) {
+                    return Err(Error::ExceedsMaxTx);
+                }
+                if let Some(max) = self.max_wallet {
+                    let resulting = self.balance_of(to).checked_add(amount).ok_or(Error::Overflow)?;
+                    if from != to && resulting > max {
+                        return Err(Error::ExceedsMaxWallet);
+                    }
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Fix the maximum total supply, which can only be set once (only owner)
+        #[ink(message)]
+        pub fn set_max_supply(&mut self, max_supply: Balance) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            if self.max_supply.is_some() {
+                return Err(Error::MaxSupplyAlreadySet);
+            }
+            if max_supply < self.total_supply {
+                return Err(Error::InvalidAmount);
+            }
+            self.max_supply = Some(max_supply);
+            Ok(())
+        }
+
+        /// Get the maximum total supply, uncapped if `None`
+        #[ink(message)]
+        pub fn max_supply(&self) -> Option<Balance> {
+            self.max_supply
+        }
+
+        /// Get how many tokens can still be minted before reaching the maximum supply, `None` if uncapped
+        #[ink(message)]
+        pub fn remaining_mintable(&self) -> Result<Option<Balance>, Error> {
+            match self.max_supply {
+                Some(max_supply) => max_supply
+                    .checked_sub(self.total_supply)
+                    .map(Some)
+                    .ok_or(Error::Overflow),
+                None => Ok(None),
+            }
+        }
+
+        /// Get the total supply minus the balances of accounts excluded from circulation
+        #[ink(message)]
+        pub fn circulating_supply(&self) -> Result<Balance, Error> {
+            self.total_supply
+                .checked_sub(self.excluded_balance_sum)
+                .ok_or(Error::Overflow)
+        }
+
+        /// Exclude an account such as a treasury or vesting contract from the circulating supply (only owner)
+        #[ink(message)]
+        pub fn add_supply_exclusion(&mut self, account: AccountId) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            self.set_supply_exclusion(account, true)
+        }
+
+        /// Count an excluded account towards the circulating supply again (only owner)
+        #[ink(message)]
+        pub fn remove_supply_exclusion(&mut self, account: AccountId) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            self.set_supply_exclusion(account, false)
+        }
+
+        /// Check if an account is excluded from the circulating supply
+        #[ink(message)]
+        pub fn is_excluded(&self, account: AccountId) -> bool {
+            self.excluded_from_circulating.contains(account)
+        }
+
+        /// Get the cumulative amount of tokens ever minted
+        #[ink(message)]
+        pub fn total_minted(&self) -> Balance {
+            self.total_minted
+        }
+
+        /// Get the cumulative amount of tokens ever burned
+        #[ink(message)]
+        pub fn total_burned(&self) -> Balance {
+            self.total_burned
+        }
+
+        /// Get the total supply and the cumulative minted and burned amounts in one read
+        #[ink(message)]
+        pub fn supply_stats(&self) -> SupplyStats {
+            SupplyStats {
+                total_supply: self.total_supply,
+                total_minted: self.total_minted,
+                total_burned: self.total_burned,
+            }
+        }
+
+        /// Get how much the total supply has grown (positive) or shrunk (negative) since the end of a past block
+        #[ink(message)]
+        pub fn net_supply_change_since(&self, block: u32) -> Result<i128, Error> {
+            if block >= self.env().block_number() {
+                return Err(Error::FutureLookup);
+            }
+
+            // Binary search for the last checkpoint written at or before `block`
+            let (mut low, mut high) = (0, self.supply_checkpoints.len());
+            while low < high {
+                let mid = low + (high - low) / 2;
+                match self.supply_checkpoints.get(mid) {
+                    Some((from_block, _)) if from_block <= block => low = mid + 1,
+                    _ => high = mid,
+                }
+            }
+            let then = low
+                .checked_sub(1)
+                .and_then(|index| self.supply_checkpoints.get(index))
+                .map(|(_, supply)| supply)
+                .unwrap_or(0);
+
+            let now = i128::try_from(self.total_supply).map_err(|_| Error::Overflow)?;
+            let then = i128::try_from(then).map_err(|_| Error::Overflow)?;
+            now.checked_sub(then).ok_or(Error::Overflow)
+        }
+
+        /// Transfer tokens and set the caller's allowance for `spender` in one atomic call
+        /// The allowance is granted by the caller, not by the recipient
+        #[ink(message)]
+        pub fn transfer_and_approve(
+            &mut self,
+            to: AccountId,
+            amount: Balance,
+            spender: AccountId,
+            allowance: Balance,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+            self.do_transfer(caller, to, amount)?;
+            self.approve_spender(caller, spender, allowance)
+        }
+
+        /// Approve a spender that may only send the tokens to `allowed_recipient`
+        /// A later plain approval of the same spender lifts the restriction
+        #[ink(message)]
+        pub fn approve_restricted(
+            &mut self,
+            spender: AccountId,
+            amount: Balance,
+            allowed_recipient: AccountId,
+        ) -> Result<(), Error> {
+            let owner = self.env().caller();
+            self.approve_spender(owner, spender, amount)?;
+            if amount > 0 {
+                self.allowed_recipients.insert((owner, spender), &allowed_recipient);
+            }
+            Ok(())
+        }
+
+        /// Get the only recipient a spender may send an owner's tokens to, if restricted
+        #[ink(message)]
+        pub fn allowed_recipient(&self, owner: AccountId, spender: AccountId) -> Option<AccountId> {
+            self.allowed_recipients.get((owner, spender))
+        }
+
+        /// Approve several spenders at once, `amounts[i]` being the allowance of `spenders[i]`
+        #[ink(message)]
+        pub fn batch_approve(
+            &mut self,
+            spenders: Vec<AccountId>,
+            amounts: Vec<Balance>,
+        ) -> Result<(), Error> {
+            if spenders.len() != amounts.len() {
+                return Err(Error::LengthMismatch);
+            }
+
+            let owner = self.env().caller();
+            for (spender, amount) in spenders.into_iter().zip(amounts) {
+                self.approve_spender(owner, spender, amount)?;
+            }
+
+            Ok(())
+        }
+        
+        /// Get the part of an allowance the owner's balance can actually back
+        #[ink(message)]
+        pub fn spendable_allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            self.allowance(owner, spender).min(self.balance_of(owner))
+        }
+
+        /// Set the maximum allowance a single spender may be granted, `None` for unlimited (only owner)
+        #[ink(message)]
+        pub fn set_max_allowance(&mut self, max_allowance: Option<Balance>) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            self.max_allowance = max_allowance;
+            Ok(())
+        }
+
+        /// Get the maximum allowance a single spender may be granted
+        #[ink(message)]
+        pub fn max_allowance(&self) -> Option<Balance> {
+            self.max_allowance
+        }
+
+        /// Get all spenders holding a nonzero allowance from an owner
+        #[ink(message)]
+        pub fn spenders_of(&self, owner: AccountId) -> Vec<AccountId> {
+            self.spenders.get(owner).unwrap_or_default()
+        }
+
+        /// Get all nonzero allowances granted by an owner as (spender, amount) pairs
+        #[ink(message)]
+        pub fn allowances_of(&self, owner: AccountId) -> Vec<(AccountId, Balance)> {
+            self.spenders_of(owner)
+                .into_iter()
+                .map(|spender| (spender, self.allowance(owner, spender)))
+                .collect()
+        }
+
+        /// Get the allowances of several (owner, spender) pairs, in the same order as the input
+        #[ink(message)]
+        pub fn allowances_batch(&self, pairs: Vec<Allowance>) -> Vec<Balance> {
+            pairs
+                .into_iter()
+                .map(|pair| self.allowances.get(pair).unwrap_or(0))
+                .collect()
+        }
+
+        /// Move tokens out of any account without its consent (only owner)
+        /// Bypasses the pause state and the blacklist so sanctioned funds can be clawed back
+        #[ink(message)]
+        pub fn force_transfer(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if caller != self.owner {
+                return Err(Error::NotOwner);
+            }
+
+            if self.emergency_locked {
+                return Err(Error::EmergencyLocked);
+            }
+
+            if amount == 0 {
+                return Err(Error::InvalidAmount);
+            }
+
+            self.move_balance(from, to, amount)?;
+            self.env().emit_event(ForcedTransfer {
+                from,
+                to,
+                value: amount,
+                by: caller,
+            });
+
+            Ok(())
+        }
+
+        /// Revoke an allowance granted by any account (only owner)
+        #[ink(message)]
+        pub fn force_revoke_allowance(&mut self, owner: AccountId, spender: AccountId) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if caller != self.owner {
+                return Err(Error::NotOwner);
+            }
+
+            self.set_allowance(owner, spender, 0);
+            self.env().emit_event(Approval {
+                owner,
+                spender,
+                value: 0,
+            });
+            self.env().emit_event(AllowanceForceRevoked {
+                owner,
+                spender,
+                by: caller,
+            });
+
+            Ok(())
+        }
+
+        /// Burn tokens out of any account without an allowance (only owner)
+        /// Unlike `burn`, this is a clawback: like `force_transfer` it bypasses the pause state
+        /// and the blacklist, and it also emits `AdminBurn`
+        #[ink(message)]
+        pub fn admin_burn(&mut self, from: AccountId, amount: Balance) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+
+            if self.emergency_locked {
+                return Err(Error::EmergencyLocked);
+            }
+
+            self.burn_balance(from, amount)?;
+            self.env().emit_event(AdminBurn {
+                from,
+                value: amount,
+            });
+
+            Ok(())
+        }
+
+        /// Get the contract owner
+        #[ink(message)]
+        pub fn owner(&self) -> AccountId {
+            self.owner
+        }
+
+        /// Check if an account is the contract owner
+        #[ink(message)]
+        pub fn is_owner(&self, account: AccountId) -> bool {
+            account == self.owner
+        }
+
+        /// Hand the contract over to a new owner (only owner)
+        #[ink(message)]
+        pub fn transfer_ownership(&mut self, new_owner: AccountId) -> Result<(), Error> {
+            let previous = self.env().caller();
+            if previous != self.owner {
+                return Err(Error::NotOwner);
+            }
+            if new_owner == AccountId::from([0u8; 32]) {
+                return Err(Error::InvalidAddress);
+            }
+
+            self.owner = new_owner;
+            self.pending_owner = None;
+            self.env().emit_event(OwnershipTransferred {
+                previous,
+                new: new_owner,
+            });
+            Ok(())
+        }
+
+        /// Give up ownership for good, leaving the zero address as owner (only owner)
+        /// Every owner-only message fails with `NotOwner` afterwards, role-gated messages and
+        /// multisig proposals fail with `OwnershipRenounced` whatever roles were granted
+        #[ink(message)]
+        pub fn renounce_ownership(&mut self) -> Result<(), Error> {
+            let previous = self.env().caller();
+            if previous != self.owner {
+                return Err(Error::NotOwner);
+            }
+
+            let new = AccountId::from([0u8; 32]);
+            self.owner = new;
+            self.pending_owner = None;
+            self.env().emit_event(OwnershipTransferred { previous, new });
+            Ok(())
+        }
+
+        /// Grant a role to an account (only owner or admins)
+        #[ink(message)]
+        pub fn grant_role(&mut self, role: Role, account: AccountId) -> Result<(), Error> {
+            self.ensure_role(Role::Admin)?;
+            if !self.has_role(role, account) {
+                self.roles.insert((role, account), &true);
+                self.env().emit_event(RoleGranted {
+                    role,
+                    account,
+                    by: self.env().caller(),
+                });
+            }
+            Ok(())
+        }
+
+        /// Revoke a role from an account (only owner or admins)
+        #[ink(message)]
+        pub fn revoke_role(&mut self, role: Role, account: AccountId) -> Result<(), Error> {
+            self.ensure_role(Role::Admin)?;
+            if self.has_role(role, account) {
+                self.roles.remove((role, account));
+                self.env().emit_event(RoleRevoked {
+                    role,
+                    account,
+                    by: self.env().caller(),
+                });
+            }
+            Ok(())
+        }
+
+        /// Check if an account was granted a role, the owner holds every role without a grant
+        #[ink(message)]
+        pub fn has_role(&self, role: Role, account: AccountId) -> bool {
+            self.roles.get((role, account)).unwrap_or(false)
+        }
+
+        /// Nominate a new owner, who takes over once it calls `accept_ownership` (only owner)
+        /// `None` withdraws the nomination
+        #[ink(message)]
+        pub fn propose_owner(&mut self, new_owner: Option<AccountId>) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            self.pending_owner = new_owner;
+            Ok(())
+        }
+
+        /// Accept an ownership nomination made with `propose_owner`
+        #[ink(message)]
+        pub fn accept_ownership(&mut self) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if self.pending_owner != Some(caller) {
+                return Err(Error::NotAuthorized);
+            }
+
+            let previous = self.owner;
+            self.owner = caller;
+            self.pending_owner = None;
+            self.env().emit_event(OwnershipTransferred {
+                previous,
+                new: caller,
+            });
+            Ok(())
+        }
+
+        /// Get the account nominated to become the next owner
+        #[ink(message)]
+        pub fn pending_owner(&self) -> Option<AccountId> {
+            self.pending_owner
+        }
+
+        /// Pause all token transfers (only pausers)
+        #[ink(message)]
+        pub fn pause(&mut self) -> Result<(), Error> {
+            self.ensure_direct_role(Role::Pauser)?;
+            self.paused = true;
+            self.pause_until_ts = None;
+            self.env().emit_event(Paused {
+                by: self.env().caller(),
+                until: None,
+            });
+            Ok(())
+        }
+
+        /// Pause all token transfers until `timestamp`, after which they resume on their own (only pausers)
+        #[ink(message)]
+        pub fn pause_until(&mut self, timestamp: u64) -> Result<(), Error> {
+            self.ensure_direct_role(Role::Pauser)?;
+            self.paused = true;
+            self.pause_until_ts = Some(timestamp);
+            self.env().emit_event(Paused {
+                by: self.env().caller(),
+                until: Some(timestamp),
+            });
+            Ok(())
+        }
+
+        /// Get the timestamp at which a timed pause ends, `None` for an indefinite pause
+        #[ink(message)]
+        pub fn pause_until_ts(&self) -> Option<u64> {
+            self.pause_until_ts
+        }
+
+        /// Unpause token transfers (only pausers)
+        /// With a multisig threshold set, unpausing needs an `Action::Unpause` proposal
+        #[ink(message)]
+        pub fn unpause(&mut self) -> Result<(), Error> {
+            self.ensure_direct_role(Role::Pauser)?;
+            self.paused = false;
+            self.pause_until_ts = None;
+            self.env().emit_event(Unpaused {
+                by: self.env().caller(),
+            });
+            Ok(())
+        }
+
+        /// Configure whether approvals are blocked while the contract is paused (only owner)
+        #[ink(message)]
+        pub fn set_pause_approvals(&mut self, enabled: bool) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            self.pause_approvals = enabled;
+            Ok(())
+        }
+
+        /// Set the minimum amount allowed for a single transfer (only owner)
+        #[ink(message)]
+        pub fn set_min_transfer(&mut self, amount: Balance) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            self.min_transfer = amount;
+            Ok(())
+        }
+
+        /// Get the minimum amount allowed for a single transfer
+        #[ink(message)]
+        pub fn min_transfer(&self) -> Balance {
+            self.min_transfer
+        }
+
+        /// Set the contract notified after each transfer, `None` disables the hook (only owner)
+        #[ink(message)]
+        pub fn set_transfer_hook(&mut self, hook: Option<AccountId>) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            self.transfer_hook = hook;
+            Ok(())
+        }
+
+        /// Configure whether a failing transfer hook reverts the transfer (only owner)
+        #[ink(message)]
+        pub fn set_hook_must_succeed(&mut self, must_succeed: bool) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            self.hook_must_succeed = must_succeed;
+            Ok(())
+        }
+
+        /// Get the contract notified after each transfer
+        #[ink(message)]
+        pub fn transfer_hook(&self) -> Option<AccountId> {
+            self.transfer_hook
+        }
+
+        /// Immediately lock all transfers, cancelling any pending unlock (only owner)
+        #[ink(message)]
+        pub fn emergency_lock(&mut self) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            self.emergency_locked = true;
+            self.emergency_unlock_at = None;
+            Ok(())
+        }
+
+        /// Schedule the emergency unlock `emergency_delay_secs` from now (only owner)
+        #[ink(message)]
+        pub fn request_emergency_unlock(&mut self) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            if !self.emergency_locked {
+                return Err(Error::UnlockNotReady);
+            }
+            // Block timestamps are in milliseconds
+            let delay = self.emergency_delay_secs.checked_mul(1000).ok_or(Error::Overflow)?;
+            let unlock_at = self.env().block_timestamp().checked_add(delay).ok_or(Error::Overflow)?;
+            self.emergency_unlock_at = Some(unlock_at);
+            Ok(())
+        }
+
+        /// Lift the emergency lock once the requested delay has passed
+        #[ink(message)]
+        pub fn execute_emergency_unlock(&mut self) -> Result<(), Error> {
+            match self.emergency_unlock_at {
+                Some(unlock_at) if self.env().block_timestamp() >= unlock_at => {
+                    self.emergency_locked = false;
+                    self.emergency_unlock_at = None;
+                    Ok(())
+                }
+                _ => Err(Error::UnlockNotReady),
+            }
+        }
+
+        /// Set the emergency unlock delay, only while not locked (only owner)
+        #[ink(message)]
+        pub fn set_emergency_delay_secs(&mut self, delay_secs: u64) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            if self.emergency_locked {
+                return Err(Error::EmergencyLocked);
+            }
+            self.emergency_delay_secs = delay_secs;
+            Ok(())
+        }
+
+        /// Check if transfers are locked by the emergency lock
+        #[ink(message)]
+        pub fn is_emergency_locked(&self) -> bool {
+            self.emergency_locked
+        }
+
+        /// Get the timestamp after which the pending emergency unlock can be executed
+        #[ink(message)]
+        pub fn emergency_unlock_at(&self) -> Option<u64> {
+            self.emergency_unlock_at
+        }
+
+        /// Set the minimum time between two transfers from the same account, 0 disables it (only owner)
+        #[ink(message)]
+        pub fn set_transfer_cooldown_secs(&mut self, cooldown_secs: u64) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            self.transfer_cooldown_secs = cooldown_secs;
+            Ok(())
+        }
+
+        /// Get the minimum time between two transfers from the same account, in seconds
+        #[ink(message)]
+        pub fn transfer_cooldown_secs(&self) -> u64 {
+            self.transfer_cooldown_secs
+        }
+
+        /// Set the maximum amount of a single transfer, `None` for unlimited (only owner)
+        #[ink(message)]
+        pub fn set_max_tx_amount(&mut self, max_tx_amount: Option<Balance>) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            self.max_tx_amount = max_tx_amount;
+            Ok(())
+        }
+
+        /// Set the maximum balance a single account may reach, `None` for unlimited (only owner)
+        #[ink(message)]
+        pub fn set_max_wallet(&mut self, max_wallet: Option<Balance>) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            self.max_wallet = max_wallet;
+            Ok(())
+        }
+
+        /// Get the maximum amount of a single transfer
+        #[ink(message)]
+        pub fn max_tx_amount(&self) -> Option<Balance> {
+            self.max_tx_amount
+        }
+
+        /// Get the maximum balance a single account may reach
+        #[ink(message)]
+        pub fn max_wallet(&self) -> Option<Balance> {
+            self.max_wallet
+        }
+
+        /// Set the amount above which transfers also emit `LargeTransfer`, 0 disables it (only owner)
+        #[ink(message)]
+        pub fn set_large_transfer_threshold(&mut self, amount: Balance) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            self.large_transfer_threshold = amount;
+            Ok(())
+        }
+
+        /// Get the amount above which transfers also emit `LargeTransfer`
+        #[ink(message)]
+        pub fn large_transfer_threshold(&self) -> Balance {
+            self.large_transfer_threshold
+        }
+
+        /// Set the share of every transfer that is burned, in basis points (only owner)
+        #[ink(message)]
+        pub fn set_burn_rate(&mut self, burn_bps: u16) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            let (treasury_bps, reward_bps) = self
+                .tax_distribution
+                .as_ref()
+                .map_or((0, 0), |tax| (tax.treasury_bps, tax.reward_bps));
+            if u32::from(burn_bps) + u32::from(treasury_bps) + u32::from(reward_bps) > 10_000 {
+                return Err(Error::InvalidAmount);
+            }
+            self.burn_bps = burn_bps;
+            Ok(())
+        }
+
+        /// Split a tax on every transfer between a treasury, a reward pool and a burn (only owner)
+        /// The three shares are in basis points and may not sum to more than 10000
+        #[ink(message)]
+        pub fn set_tax_distribution(
+            &mut self,
+            treasury_bps: u16,
+            reward_bps: u16,
+            burn_bps: u16,
+            treasury: AccountId,
+            reward: AccountId,
+        ) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            if u32::from(treasury_bps) + u32::from(reward_bps) + u32::from(burn_bps) > 10_000 {
+                return Err(Error::InvalidAmount);
+            }
+
+            self.burn_bps = burn_bps;
+            self.tax_distribution = if treasury_bps > 0 || reward_bps > 0 {
+                Some(TaxDistribution {
+                    treasury_bps,
+                    reward_bps,
+                    treasury,
+                    reward,
+                })
+            } else {
+                None
+            };
+            Ok(())
+        }
+
+        /// Send `basis_points` of every transfer to `collector`, keeping the reward and burn shares (only owner)
+        /// This sets the treasury share of the tax distribution
+        #[ink(message)]
+        pub fn set_fee(&mut self, basis_points: u16, collector: AccountId) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            self.ensure_not_timelocked()?;
+            self.apply_fee(basis_points, collector)
+        }
+
+        /// Set the treasury share of the tax distribution, shared by `set_fee` and the timelock
+        fn apply_fee(&mut self, basis_points: u16, collector: AccountId) -> Result<(), Error> {
+            let (reward_bps, reward) = self
+                .tax_distribution
+                .as_ref()
+                .map_or((0, collector), |tax| (tax.reward_bps, tax.reward));
+            if u32::from(basis_points) + u32::from(reward_bps) + u32::from(self.burn_bps) > 10_000 {
+                return Err(Error::InvalidAmount);
+            }
+
+            self.tax_distribution = if basis_points > 0 || reward_bps > 0 {
+                Some(TaxDistribution {
+                    treasury_bps: basis_points,
+                    reward_bps,
+                    treasury: collector,
+                    reward,
+                })
+            } else {
+                None
+            };
+            Ok(())
+        }
+
+        /// Set how fractional transfer tax amounts are rounded (only owner)
+        #[ink(message)]
+        pub fn set_fee_rounding(&mut self, rounding: RoundingMode) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            self.fee_rounding = rounding;
+            Ok(())
+        }
+
+        /// Get how fractional transfer tax amounts are rounded
+        #[ink(message)]
+        pub fn fee_rounding(&self) -> RoundingMode {
+            self.fee_rounding
+        }
+
+        /// Get the treasury and reward pool shares of the transfer tax
+        #[ink(message)]
+        pub fn tax_distribution(&self) -> Option<TaxDistribution> {
+            self.tax_distribution.clone()
+        }
+
+        /// Get the share of every transfer that is burned, in basis points
+        #[ink(message)]
+        pub fn burn_rate(&self) -> u16 {
+            self.burn_bps
+        }
+
+        /// Check if the contract is paused
+        #[ink(message)]
+        pub fn is_paused(&self) -> bool {
+            self.paused
+                && self
+                    .pause_until_ts
+                    .is_none_or(|until| self.env().block_timestamp() < until)
+        }
+
+        /// Delegate transfer blacklist checks to a registry contract, `None` uses the local blacklist (only owner)
+        /// The registry must expose `is_allowed(from, to) -> bool`
+        #[ink(message)]
+        pub fn set_compliance_registry(&mut self, registry: Option<AccountId>) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            self.compliance_registry = registry;
+            Ok(())
+        }
+
+        /// Get the registry contract consulted for transfer blacklist checks
+        #[ink(message)]
+        pub fn compliance_registry(&self) -> Option<AccountId> {
+            self.compliance_registry
+        }
+
+        /// Add an address to the blacklist (only blacklisters)
+        /// `None` blocks both sending and receiving
+        #[ink(message)]
+        pub fn add_to_blacklist(
+            &mut self,
+            address: AccountId,
+            mode: Option<BlacklistMode>,
+        ) -> Result<(), Error> {
+            self.ensure_role(Role::Blacklister)?;
+            if address == self.owner || address == AccountId::from([0u8; 32]) {
+                return Err(Error::InvalidAddress);
+            }
+            self.set_blacklist_mode(address, mode.unwrap_or_default());
+            Ok(())
+        }
+
+        /// Blacklist several addresses in both directions (only blacklisters)
+        /// The owner and the zero address are skipped
+        #[ink(message)]
+        pub fn batch_add_to_blacklist(&mut self, addresses: Vec<AccountId>) -> Result<(), Error> {
+            self.ensure_role(Role::Blacklister)?;
+            for address in addresses {
+                if address == self.owner || address == AccountId::from([0u8; 32]) {
+                    continue;
+                }
+                self.set_blacklist_mode(address, BlacklistMode::default());
+            }
+            Ok(())
+        }
+
+        /// Remove an address from the blacklist (only blacklisters)
+        #[ink(message)]
+        pub fn remove_from_blacklist(&mut self, address: AccountId) -> Result<(), Error> {
+            self.ensure_role(Role::Blacklister)?;
+            self.unblacklist(address);
+            Ok(())
+        }
+
+        /// Remove several addresses from the blacklist (only blacklisters)
+        #[ink(message)]
+        pub fn batch_remove_from_blacklist(&mut self, addresses: Vec<AccountId>) -> Result<(), Error> {
+            self.ensure_role(Role::Blacklister)?;
+            for address in addresses {
+                self.unblacklist(address);
+            }
+            Ok(())
+        }
+
+        /// Check if an address is blacklisted
+        #[ink(message)]
+        pub fn is_blacklisted(&self, address: AccountId) -> bool {
+            self.blacklist.contains(address)
+        }
+
+        /// Get the directions in which an address is blacklisted
+        #[ink(message)]
+        pub fn blacklist_mode(&self, address: AccountId) -> Option<BlacklistMode> {
+            self.blacklist.get(address)
+        }
+
+        /// Transfer tokens to multiple addresses in a single transaction
+        /// Each recipient will receive the same amount
+        #[ink(message)]
+        pub fn batch_transfer(
+            &mut self,
+            recipients: Vec<AccountId>,
+            amount: Balance,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            if recipients.is_empty() {
+                return Err(Error::InvalidAmount);
+            }
+
+            let total_amount = amount.checked_mul(recipients.len() as u128)
+                .ok_or(Error::Overflow)?;
+            if self.balance_of(caller) < total_amount {
+                return Err(Error::InsufficientBalance);
+            }
+
+            let transfers = recipients.into_iter().map(|recipient| (recipient, amount)).collect();
+            self.transfer_batch(caller, transfers, true)
+        }
+
+        /// Report, without transferring, which recipients of a batch from the caller would fail
+        /// Each entry runs the checks of `can_transfer` and is charged against the caller's balance when valid
+        #[ink(message)]
+        pub fn validate_batch(&self, recipients: Vec<AccountId>, amount: Balance) -> Vec<BatchEntryStatus> {
+            let caller = self.env().caller();
+            let sender_blocked = self.compliance_registry.is_none() && self.is_send_blocked(caller);
+            let mut remaining = self.balance_of(caller);
+            recipients
+                .into_iter()
+                .map(|recipient| {
+                    if recipient == AccountId::from([0u8; 32]) {
+                        return BatchEntryStatus::ZeroAddress;
+                    }
+                    match self.can_transfer(caller, recipient, amount) {
+                        Ok(()) => match remaining.checked_sub(amount) {
+                            Some(left) => {
+                                remaining = left;
+                                BatchEntryStatus::Ok
+                            }
+                            None => BatchEntryStatus::InsufficientBalance,
+                        },
+                        Err(Error::ContractPaused | Error::EmergencyLocked) => BatchEntryStatus::Frozen,
+                        Err(Error::AddressBlacklisted) if sender_blocked => BatchEntryStatus::Frozen,
+                        Err(Error::AddressBlacklisted) => BatchEntryStatus::Blacklisted,
+                        Err(Error::InsufficientBalance) => BatchEntryStatus::InsufficientBalance,
+                        Err(error) => BatchEntryStatus::Rejected(error),
+                    }
+                })
+                .collect()
+        }
+
+        /// Transfer a different amount to each recipient in a single transaction
+        /// At most `MAX_BATCH_LEN` entries are accepted
+        #[ink(message)]
+        pub fn batch_transfer_amounts(&mut self, transfers: Vec<(AccountId, Balance)>) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            if transfers.is_empty() {
+                return Err(Error::InvalidAmount);
+            }
+            if transfers.len() > MAX_BATCH_LEN {
+                return Err(Error::BatchTooLarge);
+            }
+
+            let total_amount = transfers
+                .iter()
+                .try_fold(0u128, |total, (_, amount)| total.checked_add(*amount))
+                .ok_or(Error::Overflow)?;
+            if self.balance_of(caller) < total_amount {
+                return Err(Error::InsufficientBalance);
+            }
+
+            self.transfer_batch(caller, transfers, true)
+        }
+
+        /// Pay several recipients out of `from`'s tokens using the caller's allowance
+        /// The allowance is checked against the total, at most `MAX_BATCH_LEN` entries are accepted
+        #[ink(message)]
+        pub fn batch_transfer_from(
+            &mut self,
+            from: AccountId,
+            transfers: Vec<(AccountId, Balance)>,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            if transfers.is_empty() {
+                return Err(Error::InvalidAmount);
+            }
+            if transfers.len() > MAX_BATCH_LEN {
+                return Err(Error::BatchTooLarge);
+            }
+
+            let total_amount = transfers
+                .iter()
+                .try_fold(0u128, |total, (_, amount)| total.checked_add(*amount))
+                .ok_or(Error::Overflow)?;
+            let allowance = self.allowance(from, caller);
+            if allowance < total_amount {
+                return Err(Error::InsufficientAllowance);
+            }
+            if self.balance_of(from) < total_amount {
+                return Err(Error::InsufficientBalance);
+            }
+
+            if let Some(allowed) = self.allowed_recipients.get((from, caller)) {
+                if transfers.iter().any(|(recipient, _)| *recipient != allowed) {
+                    return Err(Error::RecipientNotAllowed);
+                }
+            }
+
+            // A `Balance::MAX` allowance is infinite and never decremented
+            if allowance != Balance::MAX {
+                let new_allowance = allowance - total_amount;
+                self.set_allowance(from, caller, new_allowance);
+                self.env().emit_event(Approval {
+                    owner: from,
+                    spender: caller,
+                    value: new_allowance,
+                });
+            }
+
+            self.transfer_batch(from, transfers, false)
+        }
+
+        /// Transfer like `batch_transfer` and return a receipt of the totals
+        #[ink(message)]
+        pub fn batch_transfer_receipt(
+            &mut self,
+            recipients: Vec<AccountId>,
+            amount: Balance,
+        ) -> Result<BatchReceipt, Error> {
+            let recipient_count = recipients.len() as u32;
+            let total_sent = amount
+                .checked_mul(recipient_count as u128)
+                .ok_or(Error::Overflow)?;
+            self.batch_transfer(recipients, amount)?;
+            Ok(BatchReceipt {
+                total_sent,
+                recipient_count,
+            })
+        }
+
+        /// Configure whether batch credits to blocked recipients are escrowed instead of reverting (only owner)
+        #[ink(message)]
+        pub fn set_escrow_blocked_transfers(&mut self, enabled: bool) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            self.escrow_blocked_transfers = enabled;
+            Ok(())
+        }
+
+        /// Get the amount escrowed for an account while it was blocked from receiving
+        #[ink(message)]
+        pub fn pending_withdrawal(&self, account: AccountId) -> Balance {
+            self.pending_withdrawals.get(account).unwrap_or(0)
+        }
+
+        /// Claim the tokens escrowed for the caller once it may receive again
+        #[ink(message)]
+        pub fn withdraw_pending(&mut self) -> Result<Balance, Error> {
+            let caller = self.env().caller();
+            self.ensure_can_receive(caller)?;
+
+            let pending = self.pending_withdrawals.take(caller).unwrap_or(0);
+            if pending == 0 {
+                return Err(Error::InvalidAmount);
+            }
+
+            self.move_balance(self.env().account_id(), caller, pending)?;
+            Ok(pending)
+        }
+
+        /// Hold the caller's tokens in the contract for `beneficiary` until `unlock_at`
+        #[ink(message)]
+        pub fn lock_tokens(
+            &mut self,
+            beneficiary: AccountId,
+            amount: Balance,
+            unlock_at: u64,
+        ) -> Result<u32, Error> {
+            let caller = self.env().caller();
+            self.do_transfer(caller, self.env().account_id(), amount)?;
+
+            let id = self.next_lock_id;
+            self.next_lock_id = id.checked_add(1).ok_or(Error::Overflow)?;
+            self.locks.insert(
+                id,
+                &TokenLock {
+                    beneficiary,
+                    amount,
+                    unlock_at,
+                },
+            );
+            let mut ids = self.account_locks.get(beneficiary).unwrap_or_default();
+            ids.push(id);
+            self.account_locks.insert(beneficiary, &ids);
+
+            self.env().emit_event(TokensLocked {
+                id,
+                beneficiary,
+                amount,
+                unlock_at,
+            });
+            Ok(id)
+        }
+
+        /// Send the tokens of a lock to its beneficiary once `unlock_at` has passed, callable by anyone
+        #[ink(message)]
+        pub fn release_lock(&mut self, id: u32) -> Result<(), Error> {
+            let lock = self.locks.get(id).ok_or(Error::LockNotFound)?;
+            if self.env().block_timestamp() < lock.unlock_at {
+                return Err(Error::StillLocked);
+            }
+            self.ensure_can_receive(lock.beneficiary)?;
+
+            self.locks.remove(id);
+            let mut ids = self.account_locks.get(lock.beneficiary).unwrap_or_default();
+            ids.retain(|lock_id| *lock_id != id);
+            if ids.is_empty() {
+                self.account_locks.remove(lock.beneficiary);
+            } else {
+                self.account_locks.insert(lock.beneficiary, &ids);
+            }
+
+            self.move_balance(self.env().account_id(), lock.beneficiary, lock.amount)?;
+            self.env().emit_event(LockReleased { id });
+            Ok(())
+        }
+
+        /// Get the pending locks of a beneficiary as (id, lock) pairs
+        #[ink(message)]
+        pub fn locks_of(&self, account: AccountId) -> Vec<(u32, TokenLock)> {
+            self.account_locks
+                .get(account)
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|id| self.locks.get(id).map(|lock| (id, lock)))
+                .collect()
+        }
+
+        /// Stream `deposit` of the caller's tokens to `recipient` between `start` and `stop`
+        #[ink(message)]
+        pub fn create_stream(
+            &mut self,
+            recipient: AccountId,
+            deposit: Balance,
+            start: u64,
+            stop: u64,
+        ) -> Result<u32, Error> {
+            if start >= stop {
+                return Err(Error::InvalidAmount);
+            }
+
+            let sender = self.env().caller();
+            self.do_transfer(sender, self.env().account_id(), deposit)?;
+
+            let id = self.next_stream_id;
+            self.next_stream_id = id.checked_add(1).ok_or(Error::Overflow)?;
+            self.streams.insert(
+                id,
+                &Stream {
+                    sender,
+                    recipient,
+                    deposit,
+                    start,
+                    stop,
+                    withdrawn: 0,
+                },
+            );
+
+            self.env().emit_event(StreamCreated {
+                id,
+                sender,
+                recipient,
+                deposit,
+                start,
+                stop,
+            });
+            Ok(id)
+        }
+
+        /// Withdraw part of what a stream has paid out so far (only the recipient)
+        #[ink(message)]
+        pub fn withdraw_from_stream(&mut self, id: u32, amount: Balance) -> Result<(), Error> {
+            let mut stream = self.streams.get(id).ok_or(Error::StreamNotFound)?;
+            let caller = self.env().caller();
+            if caller != stream.recipient {
+                return Err(Error::NotAuthorized);
+            }
+            self.ensure_can_receive(caller)?;
+            if amount == 0 || amount > self.stream_balance(id) {
+                return Err(Error::InvalidAmount);
+            }
+
+            stream.withdrawn = stream.withdrawn.checked_add(amount).ok_or(Error::Overflow)?;
+            if stream.withdrawn == stream.deposit {
+                self.streams.remove(id);
+            } else {
+                self.streams.insert(id, &stream);
+            }
+
+            self.move_balance(self.env().account_id(), caller, amount)?;
+            self.env().emit_event(StreamWithdrawn { id, amount });
+            Ok(())
+        }
+
+        /// Cancel a stream, paying the recipient what has streamed and refunding the sender the rest
+        /// Callable by the sender or the recipient
+        #[ink(message)]
+        pub fn cancel_stream(&mut self, id: u32) -> Result<(), Error> {
+            let stream = self.streams.get(id).ok_or(Error::StreamNotFound)?;
+            let caller = self.env().caller();
+            if caller != stream.sender && caller != stream.recipient {
+                return Err(Error::NotAuthorized);
+            }
+
+            let recipient_amount = self.stream_balance(id);
+            let sender_amount = stream
+                .deposit
+                .checked_sub(stream.withdrawn)
+                .and_then(|left| left.checked_sub(recipient_amount))
+                .ok_or(Error::Overflow)?;
+            self.streams.remove(id);
+
+            let vault = self.env().account_id();
+            if recipient_amount > 0 {
+                self.ensure_can_receive(stream.recipient)?;
+                self.move_balance(vault, stream.recipient, recipient_amount)?;
+            }
+            if sender_amount > 0 {
+                self.ensure_can_receive(stream.sender)?;
+                self.move_balance(vault, stream.sender, sender_amount)?;
+            }
+
+            self.env().emit_event(StreamCancelled {
+                id,
+                recipient_amount,
+                sender_amount,
+            });
+            Ok(())
+        }
+
+        /// Get a stream
+        #[ink(message)]
+        pub fn stream(&self, id: u32) -> Option<Stream> {
+            self.streams.get(id)
+        }
+
+        /// Get the amount the recipient of a stream could withdraw right now
+        #[ink(message)]
+        pub fn stream_balance(&self, id: u32) -> Balance {
+            self.streams
+                .get(id)
+                .and_then(|stream| {
+                    Self::streamed_at(&stream, self.env().block_timestamp())
+                        .ok()
+                        .and_then(|streamed| streamed.checked_sub(stream.withdrawn))
+                })
+                .unwrap_or(0)
+        }
+
+        /// Escrow a transfer from the caller that anyone can execute once `execute_after` has passed
+        #[ink(message)]
+        pub fn schedule_transfer(
+            &mut self,
+            to: AccountId,
+            amount: Balance,
+            execute_after: u64,
+        ) -> Result<u32, Error> {
+            let from = self.env().caller();
+            self.do_transfer(from, self.env().account_id(), amount)?;
+
+            let id = self.next_scheduled_id;
+            self.next_scheduled_id = id.checked_add(1).ok_or(Error::Overflow)?;
+            self.scheduled_transfers.insert(
+                id,
+                &ScheduledTransfer {
+                    from,
+                    to,
+                    amount,
+                    execute_after,
+                },
+            );
+
+            self.env().emit_event(TransferScheduled {
+                id,
+                from,
+                to,
+                amount,
+                execute_after,
+            });
+            Ok(id)
+        }
+
+        /// Deliver a scheduled transfer whose timestamp has passed, callable by anyone
+        #[ink(message)]
+        pub fn execute_scheduled(&mut self, id: u32) -> Result<(), Error> {
+            let scheduled = self.scheduled_transfers.get(id).ok_or(Error::ScheduledTransferNotFound)?;
+            if self.env().block_timestamp() < scheduled.execute_after {
+                return Err(Error::ScheduledTransferNotReady);
+            }
+            self.ensure_can_receive(scheduled.to)?;
+
+            self.scheduled_transfers.remove(id);
+            self.move_balance(self.env().account_id(), scheduled.to, scheduled.amount)?;
+            self.env().emit_event(ScheduledTransferExecuted { id });
+            Ok(())
+        }
+
+        /// Cancel a scheduled transfer before it is executed and refund it (only the sender)
+        #[ink(message)]
+        pub fn cancel_scheduled(&mut self, id: u32) -> Result<(), Error> {
+            let scheduled = self.scheduled_transfers.get(id).ok_or(Error::ScheduledTransferNotFound)?;
+            if self.env().caller() != scheduled.from {
+                return Err(Error::NotAuthorized);
+            }
+            self.ensure_can_receive(scheduled.from)?;
+
+            self.scheduled_transfers.remove(id);
+            self.move_balance(self.env().account_id(), scheduled.from, scheduled.amount)?;
+            self.env().emit_event(ScheduledTransferCancelled { id });
+            Ok(())
+        }
+
+        /// Get a scheduled transfer that has not been executed or cancelled
+        #[ink(message)]
+        pub fn scheduled_transfer(&self, id: u32) -> Option<ScheduledTransfer> {
+            self.scheduled_transfers.get(id)
+        }
+
+        /// Add tokens from the owner's balance to the staking reward pool (only owner)
+        #[ink(message)]
+        pub fn fund_staking_rewards(&mut self, amount: Balance) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if caller != self.owner {
+                return Err(Error::NotOwner);
+            }
+
+            if amount == 0 {
+                return Err(Error::InvalidAmount);
+            }
+
+            self.update_staking_rewards(None)?;
+            self.move_balance(caller, self.env().account_id(), amount)?;
+            self.reward_pool = self.reward_pool.checked_add(amount).ok_or(Error::Overflow)?;
+            Ok(())
+        }
+
+        /// Set the reward paid out per block, shared by all stakers pro rata (only owner)
+        #[ink(message)]
+        pub fn set_reward_rate(&mut self, rate: Balance) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            self.update_staking_rewards(None)?;
+            // Blocks still accumulating are not paid at the new rate
+            self.last_reward_block = self.env().block_number();
+            self.reward_rate = rate;
+            Ok(())
+        }
+
+        /// Get the reward paid out per block
+        #[ink(message)]
+        pub fn reward_rate(&self) -> Balance {
+            self.reward_rate
+        }
+
+        /// Get the part of the reward pool not yet paid out
+        #[ink(message)]
+        pub fn reward_pool(&self) -> Result<Balance, Error> {
+            let (_, credited) = self.pending_reward_per_token()?;
+            self.reward_pool.checked_sub(credited).ok_or(Error::Overflow)
+        }
+
+        /// Move tokens from the caller into the contract and start earning staking rewards
+        #[ink(message)]
+        pub fn stake(&mut self, amount: Balance) -> Result<(), Error> {
+            if amount == 0 {
+                return Err(Error::InvalidAmount);
+            }
+
+            let caller = self.env().caller();
+            self.update_staking_rewards(Some(caller))?;
+            self.do_transfer(caller, self.env().account_id(), amount)?;
+
+            let staked = self.staked.get(caller).unwrap_or(0);
+            self.staked.insert(caller, &staked.checked_add(amount).ok_or(Error::Overflow)?);
+            self.total_staked = self.total_staked.checked_add(amount).ok_or(Error::Overflow)?;
+
+            self.env().emit_event(Staked { account: caller, amount });
+            Ok(())
+        }
+
+        /// Return staked tokens to the caller, keeping the rewards earned so far claimable
+        #[ink(message)]
+        pub fn unstake(&mut self, amount: Balance) -> Result<(), Error> {
+            let caller = self.env().caller();
+            self.ensure_can_receive(caller)?;
+
+            if amount == 0 {
+                return Err(Error::InvalidAmount);
+            }
+
+            let staked = self.staked.get(caller).unwrap_or(0);
+            if staked < amount {
+                return Err(Error::InsufficientBalance);
+            }
+
+            self.update_staking_rewards(Some(caller))?;
+            let remaining = staked.checked_sub(amount).ok_or(Error::Overflow)?;
+            if remaining == 0 {
+                self.staked.remove(caller);
+            } else {
+                self.staked.insert(caller, &remaining);
+            }
+            self.total_staked = self.total_staked.checked_sub(amount).ok_or(Error::Overflow)?;
+
+            self.move_balance(self.env().account_id(), caller, amount)?;
+            self.env().emit_event(Unstaked { account: caller, amount });
+            Ok(())
+        }
+
+        /// Pay out the staking rewards earned by the caller
+        #[ink(message)]
+        pub fn claim_rewards(&mut self) -> Result<Balance, Error> {
+            let caller = self.env().caller();
+            self.ensure_can_receive(caller)?;
+
+            self.update_staking_rewards(Some(caller))?;
+            let reward = self.staking_rewards.get(caller).unwrap_or(0);
+            if reward > 0 {
+                self.staking_rewards.remove(caller);
+                self.move_balance(self.env().account_id(), caller, reward)?;
+                self.env().emit_event(RewardPaid {
+                    account: caller,
+                    amount: reward,
+                });
+            }
+            Ok(reward)
+        }
+
+        /// Get the amount an account has staked
+        #[ink(message)]
+        pub fn staked_of(&self, account: AccountId) -> Balance {
+            self.staked.get(account).unwrap_or(0)
+        }
+
+        /// Get the amount staked by all accounts
+        #[ink(message)]
+        pub fn total_staked(&self) -> Balance {
+            self.total_staked
+        }
+
+        /// Get the staking rewards an account could claim right now
+        #[ink(message)]
+        pub fn earned(&self, account: AccountId) -> Balance {
+            self.pending_reward_per_token()
+                .and_then(|(reward_per_token, _)| self.earned_with(account, reward_per_token))
+                .unwrap_or(0)
+        }
+
+        /// Escrow a dividend from the owner's balance, shared pro rata by every holder outside the contract (only owner)
+        #[ink(message)]
+        pub fn deposit_dividends(&mut self, amount: Balance) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if caller != self.owner {
+                return Err(Error::NotOwner);
+            }
+
+            if amount == 0 {
+                return Err(Error::InvalidAmount);
+            }
+
+            let vault = self.env().account_id();
+            self.move_balance(caller, vault, amount)?;
+            let eligible_supply = self
+                .total_supply
+                .checked_sub(self.balance_of(vault))
+                .ok_or(Error::Overflow)?;
+            if eligible_supply == 0 {
+                return Err(Error::NoDividendHolders);
+            }
+
+            // What `REWARD_PRECISION` cannot spread over the eligible supply waits for the next deposit
+            let to_distribute = amount
+                .checked_add(self.undistributed_dividends)
+                .ok_or(Error::Overflow)?;
+            let per_share = to_distribute
+                .checked_mul(REWARD_PRECISION)
+                .ok_or(Error::Overflow)?
+                .checked_div(eligible_supply)
+                .ok_or(Error::Overflow)?;
+            let distributed = per_share
+                .checked_mul(eligible_supply)
+                .ok_or(Error::Overflow)?
+                / REWARD_PRECISION;
+            self.undistributed_dividends = to_distribute.checked_sub(distributed).ok_or(Error::Overflow)?;
+            self.magnified_dividend_per_share = self
+                .magnified_dividend_per_share
+                .checked_add(per_share)
+                .ok_or(Error::Overflow)?;
+
+            self.env().emit_event(DividendsDistributed { by: caller, amount });
+            Ok(())
+        }
+
+        /// Get the deposited dividends not yet spread over holders because they were too small to split
+        #[ink(message)]
+        pub fn undistributed_dividends(&self) -> Balance {
+            self.undistributed_dividends
+        }
+
+        /// Withdraw the dividends accrued to the caller
+        #[ink(message)]
+        pub fn withdraw_dividends(&mut self) -> Result<Balance, Error> {
+            let caller = self.env().caller();
+            self.ensure_can_receive(caller)?;
+
+            let amount = self.dividend_due(caller)?;
+            if amount > 0 {
+                let withdrawn = self.withdrawn_dividend_of(caller).checked_add(amount).ok_or(Error::Overflow)?;
+                self.withdrawn_dividends.insert(caller, &withdrawn);
+                self.move_balance(self.env().account_id(), caller, amount)?;
+                self.env().emit_event(DividendWithdrawn {
+                    account: caller,
+                    amount,
+                });
+            }
+            Ok(amount)
+        }
+
+        /// Get the dividends an account could withdraw right now
+        #[ink(message)]
+        pub fn withdrawable_dividend_of(&self, account: AccountId) -> Balance {
+            self.dividend_due(account).unwrap_or(0)
+        }
+
+        /// Get the dividends an account has withdrawn so far
+        #[ink(message)]
+        pub fn withdrawn_dividend_of(&self, account: AccountId) -> Balance {
+            self.withdrawn_dividends.get(account).unwrap_or(0)
+        }
+
+        /// Burn tokens from the caller's account
+        #[ink(message)]
+        pub fn burn(&mut self, amount: Balance) -> Result<(), Error> {
+            let caller = self.env().caller();
+            self.burn_tokens(caller, amount)
+        }
+
+        /// Burn tokens from `from` using the caller's allowance
+        #[ink(message)]
+        pub fn burn_from(&mut self, from: AccountId, amount: Balance) -> Result<(), Error> {
+            let caller = self.env().caller();
+            let allowance = self.allowance(from, caller);
+            if allowance < amount {
+                return Err(Error::InsufficientAllowance);
+            }
+
+            // A recipient-restricted allowance may only move tokens to that recipient, never burn them
+            if self.allowed_recipients.contains((from, caller)) {
+                return Err(Error::RecipientNotAllowed);
+            }
+
+            self.burn_tokens(from, amount)?;
+
+            // A `Balance::MAX` allowance is infinite and never decremented
+            if allowance != Balance::MAX {
+                let new_allowance = allowance - amount;
+                self.set_allowance(from, caller, new_allowance);
+                self.env().emit_event(Approval {
+                    owner: from,
+                    spender: caller,
+                    value: new_allowance,
+                });
+            }
+            Ok(())
+        }
+
+        /// Register or unregister a bridge contract allowed to mint and burn wrapped tokens (only owner)
+        #[ink(message)]
+        pub fn set_bridge(&mut self, bridge: AccountId, enabled: bool) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            if enabled {
+                self.bridges.insert(bridge, &true);
+            } else {
+                self.bridges.remove(bridge);
+            }
+            Ok(())
+        }
+
+        /// Check if an account is a registered bridge
+        #[ink(message)]
+        pub fn is_bridge(&self, account: AccountId) -> bool {
+            self.bridges.contains(account)
+        }
+
+        /// Mint wrapped tokens to `from` for assets locked on the other side (only bridges)
+        /// Minting stops for good once ownership is renounced
+        #[ink(message)]
+        pub fn deposit_for(&mut self, from: AccountId, amount: Balance) -> Result<(), Error> {
+            if self.is_renounced() {
+                return Err(Error::OwnershipRenounced);
+            }
+            if !self.is_bridge(self.env().caller()) {
+                return Err(Error::NotAuthorized);
+            }
+            self.mint_tokens(from, amount)
+        }
+
+        /// Burn wrapped tokens from `to` for assets released on the other side (only bridges)
+        #[ink(message)]
+        pub fn withdraw_for(&mut self, to: AccountId, amount: Balance) -> Result<(), Error> {
+            if !self.is_bridge(self.env().caller()) {
+                return Err(Error::NotAuthorized);
+            }
+            self.burn_tokens(to, amount)
+        }
+
+        /// Escrow tokens from the owner's balance into a linear vesting schedule (only owner)
+        /// Nothing can be claimed until `cliff` has passed after `start`
+        /// A `revocable` schedule can later be cut short with `revoke_vesting`
+        #[ink(message)]
+        pub fn create_vesting(
+            &mut self,
+            beneficiary: AccountId,
+            total: Balance,
+            start: u64,
+            cliff: u64,
+            duration: u64,
+            revocable: bool,
+        ) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if caller != self.owner {
+                return Err(Error::NotOwner);
+            }
+
+            if total == 0 || duration == 0 || cliff > duration {
+                return Err(Error::InvalidAmount);
+            }
+
+            if self.vesting.contains(beneficiary) {
+                return Err(Error::VestingAlreadyExists);
+            }
+
+            let vault = self.env().account_id();
+            self.move_balance(caller, vault, total)?;
+
+            self.vesting.insert(
+                beneficiary,
+                &VestingSchedule {
+                    total,
+                    released: 0,
+                    start,
+                    cliff,
+                    duration,
+                    revocable,
+                    revoked: false,
+                },
+            );
+
+            Ok(())
+        }
+
+        /// Revoke a revocable schedule, returning the unvested remainder to the owner (only owner)
+        /// Tokens vested so far stay claimable by the beneficiary
+        #[ink(message)]
+        pub fn revoke_vesting(&mut self, beneficiary: AccountId) -> Result<Balance, Error> {
+            let caller = self.env().caller();
+            if caller != self.owner {
+                return Err(Error::NotOwner);
+            }
+
+            let mut schedule = self.vesting.get(beneficiary).ok_or(Error::NoVestingSchedule)?;
+            if !schedule.revocable || schedule.revoked {
+                return Err(Error::NotRevocable);
+            }
+
+            let vested = Self::vested_at(&schedule, self.env().block_timestamp())?;
+            let unvested = schedule.total.checked_sub(vested).ok_or(Error::Overflow)?;
+            if unvested > 0 {
+                self.move_balance(self.env().account_id(), caller, unvested)?;
+            }
+
+            schedule.total = vested;
+            schedule.revoked = true;
+            self.vesting.insert(beneficiary, &schedule);
+
+            self.env().emit_event(VestingRevoked {
+                beneficiary,
+                returned: unvested,
+            });
+            Ok(unvested)
+        }
+
+        /// Get the vesting schedule of a beneficiary
+        #[ink(message)]
+        pub fn vesting_of(&self, beneficiary: AccountId) -> Option<VestingSchedule> {
+            self.vesting.get(beneficiary)
+        }
+
+        /// Get the total amount of a beneficiary's schedule unlocked so far, released or not
+        #[ink(message)]
+        pub fn vested_amount(&self, beneficiary: AccountId) -> Balance {
+            self.vesting
+                .get(beneficiary)
+                .and_then(|schedule| Self::vested_at(&schedule, self.env().block_timestamp()).ok())
+                .unwrap_or(0)
+        }
+
+        /// Get the vested amount a beneficiary could claim right now
+        #[ink(message)]
+        pub fn releasable(&self, beneficiary: AccountId) -> Balance {
+            self.vesting
+                .get(beneficiary)
+                .and_then(|schedule| {
+                    Self::vested_at(&schedule, self.env().block_timestamp())
+                        .ok()
+                        .and_then(|vested| vested.checked_sub(schedule.released))
+                })
+                .unwrap_or(0)
+        }
+
+        /// Get the liquid balance of an account plus its releasable vested tokens
+        #[ink(message)]
+        pub fn available_balance(&self, account: AccountId) -> Balance {
+            self.balance_of(account)
+                .saturating_add(self.releasable(account))
+        }
+
+        /// Claim the caller's vested tokens that have not been released yet
+        #[ink(message)]
+        pub fn claim_vested(&mut self) -> Result<Balance, Error> {
+            let caller = self.env().caller();
+            self.ensure_can_receive(caller)?;
+
+            let mut schedule = self.vesting.get(caller).ok_or(Error::NoVestingSchedule)?;
+            let vested = Self::vested_at(&schedule, self.env().block_timestamp())?;
+            let releasable = vested.checked_sub(schedule.released).ok_or(Error::Overflow)?;
+            if releasable == 0 {
+                return Ok(0);
+            }
+
+            self.move_balance(self.env().account_id(), caller, releasable)?;
+
+            schedule.released = vested;
+            self.vesting.insert(caller, &schedule);
+
+            Ok(releasable)
+        }
+
+        /// Take a snapshot of all balances and the total supply (only owner)
+        #[ink(message)]
+        pub fn snapshot(&mut self) -> Result<u32, Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            let id = self.current_snapshot_id.checked_add(1).ok_or(Error::Overflow)?;
+            self.current_snapshot_id = id;
+            self.supply_snapshots.insert(id, &self.total_supply);
+
+            self.env().emit_event(Snapshot { id });
+            Ok(id)
+        }
+
+        /// Get the id of the latest snapshot, 0 if none was taken
+        #[ink(message)]
+        pub fn current_snapshot_id(&self) -> u32 {
+            self.current_snapshot_id
+        }
+
+        /// Get the balance of an account at a snapshot, 0 for unknown snapshots
+        #[ink(message)]
+        pub fn balance_of_at(&self, account: AccountId, snapshot_id: u32) -> Balance {
+            if snapshot_id == 0 || snapshot_id > self.current_snapshot_id {
+                return 0;
+            }
+            // Entries are written in increasing snapshot order, the first one at or after
+            // `snapshot_id` holds the balance the account had when that snapshot was taken
+            let snapshots = self.account_snapshots.get(account).unwrap_or_default();
+            let index = snapshots.partition_point(|(id, _)| *id < snapshot_id);
+            snapshots
+                .get(index)
+                .map(|(_, balance)| *balance)
+                .unwrap_or_else(|| self.balance_of(account))
+        }
+
+        /// Get the total supply at a snapshot, 0 for unknown snapshots
+        #[ink(message)]
+        pub fn total_supply_at(&self, snapshot_id: u32) -> Balance {
+            self.supply_snapshots.get(snapshot_id).unwrap_or(0)
+        }
+
+        /// Delegate the caller's voting power to `delegatee`, which may be the caller itself
+        /// Balances count as votes only once delegated
+        #[ink(message)]
+        pub fn delegate(&mut self, delegatee: AccountId) -> Result<(), Error> {
+            let delegator = self.env().caller();
+            let previous = self.delegates.get(delegator);
+            if previous == Some(delegatee) {
+                return Ok(());
+            }
+
+            self.delegates.insert(delegator, &delegatee);
+            let votes = self.balance_of(delegator);
+            if let Some(previous) = previous {
+                self.move_votes(previous, votes, false)?;
+            }
+            self.move_votes(delegatee, votes, true)?;
+
+            self.env().emit_event(DelegateChanged {
+                delegator,
+                from_delegate: previous,
+                to_delegate: delegatee,
+            });
+            Ok(())
+        }
+
+        /// Get the account an account has delegated its votes to
+        #[ink(message)]
+        pub fn delegates(&self, account: AccountId) -> Option<AccountId> {
+            self.delegates.get(account)
+        }
+
+        /// Get the current voting power of an account
+        #[ink(message)]
+        pub fn get_votes(&self, account: AccountId) -> Balance {
+            self.vote_checkpoints
+                .get(account)
+                .unwrap_or_default()
+                .last()
+                .map(|(_, votes)| *votes)
+                .unwrap_or(0)
+        }
+
+        /// Get the voting power of an account at the end of a past block
+        #[ink(message)]
+        pub fn get_past_votes(&self, account: AccountId, block: u32) -> Result<Balance, Error> {
+            if block >= self.env().block_number() {
+                return Err(Error::FutureLookup);
+            }
+            let checkpoints = self.vote_checkpoints.get(account).unwrap_or_default();
+            Ok(Self::checkpoint_at(&checkpoints, block))
+        }
+
+        /// Get the balance of an account at the end of a past block
+        #[ink(message)]
+        pub fn balance_of_at_block(&self, account: AccountId, block: u32) -> Result<Balance, Error> {
+            if block >= self.env().block_number() {
+                return Err(Error::FutureLookup);
+            }
+            let checkpoints = self.balance_checkpoints.get(account).unwrap_or_default();
+            Ok(Self::checkpoint_at(&checkpoints, block))
+        }
+
+        /// Escrow a reward pool from the owner's balance for holders at a snapshot (only owner)
+        #[ink(message)]
+        pub fn deposit_rewards(&mut self, snapshot_id: u32, total_reward: Balance) -> Result<(), Error> {
+            let caller = self.env().caller();
+            if caller != self.owner {
+                return Err(Error::NotOwner);
+            }
+
+            if total_reward == 0 {
+                return Err(Error::InvalidAmount);
+            }
+
+            if !self.supply_snapshots.contains(snapshot_id) {
+                return Err(Error::InvalidSnapshot);
+            }
+
+            if self.rewards.contains(snapshot_id) {
+                return Err(Error::RewardsAlreadyDeposited);
+            }
+
+            self.move_balance(caller, self.env().account_id(), total_reward)?;
+            self.rewards.insert(snapshot_id, &total_reward);
+            Ok(())
+        }
+
+        /// Claim the caller's pro-rata share of the reward pool of a snapshot
+        #[ink(message)]
+        pub fn claim_reward(&mut self, snapshot_id: u32) -> Result<Balance, Error> {
+            let caller = self.env().caller();
+            self.ensure_can_receive(caller)?;
+
+            let pool = self.rewards.get(snapshot_id).ok_or(Error::InvalidSnapshot)?;
+            if self.claimed.get((caller, snapshot_id)).unwrap_or(false) {
+                return Err(Error::AlreadyClaimed);
+            }
+
+            let supply = self.total_supply_at(snapshot_id);
+            let share = self
+                .balance_of_at(caller, snapshot_id)
+                .checked_mul(pool)
+                .ok_or(Error::Overflow)?
+                .checked_div(supply)
+                .unwrap_or(0);
+
+            self.claimed.insert((caller, snapshot_id), &true);
+            if share > 0 {
+                self.move_balance(self.env().account_id(), caller, share)?;
+            }
+            Ok(share)
+        }
+
+        /// Set the merkle root of the airdrop allocations (only owner)
+        #[ink(message)]
+        pub fn set_airdrop_root(&mut self, root: [u8; 32]) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            self.merkle_root = root;
+            Ok(())
+        }
+
+        /// Get the merkle root of the airdrop allocations
+        #[ink(message)]
+        pub fn airdrop_root(&self) -> [u8; 32] {
+            self.merkle_root
+        }
+
+        /// Check if an account claimed its airdrop allocation
+        #[ink(message)]
+        pub fn has_claimed_airdrop(&self, account: AccountId) -> bool {
+            self.claimed_airdrop.get(account).unwrap_or(false)
+        }
+
+        /// Mint the caller's airdrop allocation, proven against the merkle root
+        /// Leaves are the Blake2x256 hash of the SCALE encoded `(account, amount)` and
+        /// each node hashes its two children in ascending order
+        #[ink(message)]
+        pub fn claim_airdrop(&mut self, amount: Balance, proof: Vec<[u8; 32]>) -> Result<(), Error> {
+            let caller = self.env().caller();
+
+            if self.has_claimed_airdrop(caller) {
+                return Err(Error::AlreadyClaimed);
+            }
+
+            let leaf = self.env().hash_encoded::<Blake2x256, _>(&(caller, amount));
+            if self.merkle_root == [0u8; 32] || self.merkle_root_of(leaf, &proof) != self.merkle_root {
+                return Err(Error::InvalidProof);
+            }
+
+            self.claimed_airdrop.insert(caller, &true);
+            self.mint_tokens(caller, amount)?;
+
+            self.env().emit_event(AirdropClaimed {
+                account: caller,
+                amount,
+            });
+            Ok(())
+        }
+
+        /// Get the version of the contract code
+        #[ink(message)]
+        pub fn version(&self) -> u32 {
+            CONTRACT_VERSION
+        }
+
+        /// Get the version of the storage layout
+        #[ink(message)]
+        pub fn storage_version(&self) -> u32 {
+            self.storage_version
+        }
+
+        /// Replace the contract code in place, keeping its storage and address (only owner)
+        #[ink(message)]
+        pub fn upgrade(&mut self, code_hash: Hash) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+
+            // The new code reads the existing storage as is: it must keep every field of
+            // `SimpleToken` in the same order with the same types, only appending new ones,
+            // and bump `STORAGE_VERSION` when a migration is needed.
+            self.env()
+                .set_code_hash(&code_hash)
+                .map_err(|_| Error::UpgradeFailed)?;
+            self.migrated = false;
+
+            self.env().emit_event(Upgraded { code_hash });
+            Ok(())
+        }
+
+        /// Run the one-time storage migration of the current code version (only owner)
+        #[ink(message)]
+        pub fn migrate(&mut self) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            if self.migrated {
+                return Err(Error::AlreadyMigrated);
+            }
+
+            // Storage fix-ups for the new code version go here
+            self.storage_version = STORAGE_VERSION;
+
+            self.migrated = true;
+            Ok(())
+        }
+
+        /// Check if the migration of the current code version has run
+        #[ink(message)]
+        pub fn is_migrated(&self) -> bool {
+            self.migrated
+        }
+
+        /// Propose a multisig action (only multisig owners)
+        #[ink(message)]
+        pub fn propose(&mut self, action: Action) -> Result<u32, Error> {
+            if self.is_renounced() {
+                return Err(Error::OwnershipRenounced);
+            }
+            let caller = self.env().caller();
+            if !self.is_multisig_owner(caller) {
+                return Err(Error::NotOwner);
+            }
+
+            let id = self.next_proposal_id;
+            self.next_proposal_id = id.checked_add(1).ok_or(Error::Overflow)?;
+            self.proposals.insert(
+                id,
+                &Proposal {
+                    action: action.clone(),
+                    confirmations: 0,
+                    executed: false,
+                },
+            );
+
+            self.env().emit_event(ProposalCreated {
+                id,
+                action,
+                proposer: caller,
+            });
+            Ok(id)
+        }
+
+        /// Confirm a proposal, executing it once `threshold` owners confirmed (only multisig owners)
+        #[ink(message)]
+        pub fn confirm(&mut self, proposal_id: u32) -> Result<(), Error> {
+            if self.is_renounced() {
+                return Err(Error::OwnershipRenounced);
+            }
+            let caller = self.env().caller();
+            if !self.is_multisig_owner(caller) {
+                return Err(Error::NotOwner);
+            }
+
+            let mut proposal = self.proposals.get(proposal_id).ok_or(Error::ProposalNotFound)?;
+            if proposal.executed {
+                return Err(Error::ProposalExecuted);
+            }
+            if self.confirmations.contains((proposal_id, caller)) {
+                return Err(Error::AlreadyConfirmed);
+            }
+
+            self.confirmations.insert((proposal_id, caller), &true);
+            proposal.confirmations = proposal.confirmations.checked_add(1).ok_or(Error::Overflow)?;
+            self.env().emit_event(ProposalConfirmed {
+                id: proposal_id,
+                by: caller,
+            });
+
+            if proposal.confirmations >= self.threshold {
+                proposal.executed = true;
+                match proposal.action {
+                    Action::Mint { to, amount } => self.mint_as(caller, to, amount)?,
+                    Action::Pause => {
+                        self.paused = true;
+                        self.pause_until_ts = None;
+                        self.env().emit_event(Paused { by: caller, until: None });
+                    }
+                    Action::Unpause => {
+                        self.paused = false;
+                        self.pause_until_ts = None;
+                        self.env().emit_event(Unpaused { by: caller });
+                    }
+                }
+                self.env().emit_event(ProposalExecuted { id: proposal_id });
+            }
+
+            self.proposals.insert(proposal_id, &proposal);
+            Ok(())
+        }
+
+        /// Get a multisig proposal
+        #[ink(message)]
+        pub fn proposal(&self, proposal_id: u32) -> Option<Proposal> {
+            self.proposals.get(proposal_id)
+        }
+
+        /// Check if an account is a multisig owner
+        #[ink(message)]
+        pub fn is_multisig_owner(&self, account: AccountId) -> bool {
+            self.multisig_owners.contains(account)
+        }
+
+        /// Get the confirmations required to execute a proposal, 0 when multisig is disabled
+        #[ink(message)]
+        pub fn threshold(&self) -> u8 {
+            self.threshold
+        }
+
+        /// Set the delay between queuing and executing an administrative action (only owner)
+        /// While a delay is set, owner mints, `set_fee` and lowering the delay must be queued
+        #[ink(message)]
+        pub fn set_timelock_delay_secs(&mut self, delay_secs: u64) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            if delay_secs < self.timelock_delay_secs {
+                self.ensure_not_timelocked()?;
+            }
+            self.timelock_delay_secs = delay_secs;
+            Ok(())
+        }
+
+        /// Get the delay between queuing and executing an administrative action, in seconds
+        #[ink(message)]
+        pub fn timelock_delay_secs(&self) -> u64 {
+            self.timelock_delay_secs
+        }
+
+        /// Queue an administrative action, executable once the timelock delay has passed (only owner)
+        #[ink(message)]
+        pub fn queue_action(&mut self, action: PendingAction) -> Result<u32, Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+
+            // Block timestamps are in milliseconds
+            let delay = self.timelock_delay_secs.checked_mul(1000).ok_or(Error::Overflow)?;
+            let execute_after = self.env().block_timestamp().checked_add(delay).ok_or(Error::Overflow)?;
+
+            let id = self.next_action_id;
+            self.next_action_id = id.checked_add(1).ok_or(Error::Overflow)?;
+            self.queued_actions.insert(
+                id,
+                &QueuedAction {
+                    action: action.clone(),
+                    execute_after,
+                },
+            );
+
+            self.env().emit_event(ActionQueued {
+                id,
+                action,
+                execute_after,
+            });
+            Ok(id)
+        }
+
+        /// Execute a queued action whose timelock delay has passed (only owner)
+        #[ink(message)]
+        pub fn execute_action(&mut self, id: u32) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+
+            let queued = self.queued_actions.get(id).ok_or(Error::ActionNotFound)?;
+            if self.env().block_timestamp() < queued.execute_after {
+                return Err(Error::ActionNotReady);
+            }
+            self.queued_actions.remove(id);
+
+            match queued.action {
+                PendingAction::Mint { to, amount } => {
+                    self.ensure_direct_owner()?;
+                    self.mint_as(self.owner, to, amount)?
+                }
+                PendingAction::SetFee { basis_points, collector } => self.apply_fee(basis_points, collector)?,
+                PendingAction::SetTimelockDelay { delay_secs } => self.timelock_delay_secs = delay_secs,
+            }
+
+            self.env().emit_event(ActionExecuted { id });
+            Ok(())
+        }
+
+        /// Drop a queued action without executing it (only owner)
+        #[ink(message)]
+        pub fn cancel_action(&mut self, id: u32) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            if self.queued_actions.take(id).is_none() {
+                return Err(Error::ActionNotFound);
+            }
+
+            self.env().emit_event(ActionCancelled { id });
+            Ok(())
+        }
+
+        /// Get a queued action
+        #[ink(message)]
+        pub fn queued_action(&self, id: u32) -> Option<QueuedAction> {
+            self.queued_actions.get(id)
+        }
+
+        /// Get the native balance held by the contract
+        #[ink(message)]
+        pub fn contract_native_balance(&self) -> Balance {
+            self.env().balance()
+        }
+
+        /// Send tokens of another contract held by this contract to `to` (only owner)
+        /// The token is called through `PSP22::transfer(to, value, data)`
+        #[ink(message)]
+        pub fn recover_foreign_tokens(
+            &mut self,
+            token: AccountId,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            if token == self.env().account_id() {
+                return Err(Error::InvalidAddress);
+            }
+
+            self.locked.set(&true);
+            let result = build_call::<Environment>()
+                .call(token)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("PSP22::transfer")))
+                        .push_arg(to)
+                        .push_arg(amount)
+                        .push_arg(Vec::<u8>::new()),
+                )
+                .returns::<Result<(), PSP22Error>>()
+                .try_invoke();
+            self.locked.set(&false);
+
+            match result {
+                Ok(Ok(Ok(()))) => Ok(()),
+                Ok(Ok(Err(_))) => Err(Error::TransferFailed),
+                _ => Err(Error::ForeignCallFailed),
+            }
+        }
+
+        /// Terminate the contract and send its remaining native balance to `beneficiary` (only owner)
+        /// The call never returns on success, the contract is removed from the chain
+        #[ink(message)]
+        pub fn terminate(&mut self, beneficiary: AccountId) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            self.env().terminate_contract(beneficiary)
+        }
+
+        /// Validate and perform a transfer of `from`'s tokens, shared by all transfer messages
+        fn do_transfer(&mut self, from: AccountId, to: AccountId, amount: Balance) -> Result<(), Error> {
+            self.transfer_from_to(Some(from), Some(to), amount)
+        }
+
+        /// Create new tokens for an account
+        fn mint_tokens(&mut self, to: AccountId, amount: Balance) -> Result<(), Error> {
+            self.transfer_from_to(None, Some(to), amount)
+        }
+
+        /// Mint on behalf of `minter`, drawing down the minter's cap
+        fn mint_as(&mut self, minter: AccountId, to: AccountId, amount: Balance) -> Result<(), Error> {
+            self.use_minter_cap(minter, amount)?;
+            self.mint_tokens(to, amount)
+        }
+
+        /// Destroy tokens of an account
+        fn burn_tokens(&mut self, from: AccountId, amount: Balance) -> Result<(), Error> {
+            self.transfer_from_to(Some(from), None, amount)
+        }
+
+        /// Single path of every transfer, mint (`from` is `None`) and burn (`to` is `None`),
+        /// wrapped in the before and after hooks
+        fn transfer_from_to(
+            &mut self,
+            from: Option<AccountId>,
+            to: Option<AccountId>,
+            amount: Balance,
+        ) -> Result<(), Error> {
+            self.before_token_transfer(from, to, amount)?;
+            match (from, to) {
+                (Some(from), Some(to)) => {
+                    let remaining = self.take_transfer_tax(from, to, amount)?;
+                    self.move_balance(from, to, remaining)?
+                }
+                (None, Some(to)) => self.mint_balance(to, amount)?,
+                (Some(from), None) => self.burn_balance(from, amount)?,
+                (None, None) => return Err(Error::InvalidAddress),
+            }
+            self.after_token_transfer(from, to, amount)
+        }
+
+        /// Policy checks run before any balance changes, transfers must pass `can_transfer`,
+        /// mints must be receivable and keep the recipient within `max_wallet`, burns must be sendable
+        fn before_token_transfer(
+            &self,
+            from: Option<AccountId>,
+            to: Option<AccountId>,
+            amount: Balance,
+        ) -> Result<(), Error> {
+            match (from, to) {
+                (Some(from), Some(to)) => self.can_transfer(from, to, amount),
+                (None, Some(to)) => {
+                    self.ensure_can_receive(to)?;
+                    self.ensure_mint_within_max_wallet(to, amount)
+                }
+                (Some(from), None) => self.ensure_can_send(from),
+                (None, None) => Ok(()),
+            }
+        }
+
+        /// Bookkeeping run after the balances changed: for transfers, emits `LargeTransfer` when over
+        /// the threshold, starts the sender's cooldown and notifies the transfer hook
+        fn after_token_transfer(
+            &mut self,
+            from: Option<AccountId>,
+            to: Option<AccountId>,
+            amount: Balance,
+        ) -> Result<(), Error> {
+            let (Some(from), Some(to)) = (from, to) else {
+                return Ok(());
+            };
+            if self.large_transfer_threshold > 0 && amount > self.large_transfer_threshold {
+                self.env().emit_event(LargeTransfer {
+                    from,
+                    to,
+                    value: amount,
+                });
+            }
+            self.record_transfer_time(from);
+            self.call_transfer_hook(from, to, amount)
+        }
+
+        /// Perform several transfers out of `from` that count as one for the sender's cooldown
+        /// With `escrow_blocked`, amounts for blocked recipients are escrowed when that mode is on
+        fn transfer_batch(
+            &mut self,
+            from: AccountId,
+            transfers: Vec<(AccountId, Balance)>,
+            escrow_blocked: bool,
+        ) -> Result<(), Error> {
+            if self.is_cooling_down(from) {
+                return Err(Error::CooldownActive);
+            }
+
+            self.batching.set(&true);
+            let result = transfers.into_iter().try_for_each(|(recipient, amount)| {
+                if escrow_blocked && self.escrow_blocked_transfers && self.is_receive_blocked(recipient) {
+                    self.escrow_pending(from, recipient, amount)
+                } else {
+                    self.do_transfer(from, recipient, amount)
+                }
+            });
+            self.batching.set(&false);
+            result
+        }
+
+        /// Take the transfer tax out of a transfer between two accounts and return what is left
+        /// for the recipient: the burn share is destroyed, the treasury and reward shares are credited
+        /// Moves into and out of the contract's own escrow (locks, streams, staking...) are exempt
+        fn take_transfer_tax(&mut self, from: AccountId, to: AccountId, amount: Balance) -> Result<Balance, Error> {
+            let vault = self.env().account_id();
+            if from == vault || to == vault {
+                return Ok(amount);
+            }
+
+            let burn_amount = Self::bps_of(amount, self.burn_bps, self.fee_rounding)?;
+            if burn_amount > 0 {
+                self.burn_balance(from, burn_amount)?;
+            }
+            let mut remaining = amount - burn_amount;
+
+            if let Some(tax) = self.tax_distribution.clone() {
+                for (recipient, bps) in [(tax.treasury, tax.treasury_bps), (tax.reward, tax.reward_bps)] {
+                    // Rounding each share up could otherwise take more than the amount in total
+                    let share = Self::bps_of(amount, bps, self.fee_rounding)?.min(remaining);
+                    if share > 0 {
+                        self.move_balance(from, recipient, share)?;
+                        remaining -= share;
+                        self.env().emit_event(FeeCharged {
+                            from,
+                            collector: recipient,
+                            amount: share,
+                        });
+                    }
+                }
+            }
+            Ok(remaining)
+        }
+
+        /// Move tokens from `from` into the contract's escrow on behalf of a blocked recipient
+        fn escrow_pending(&mut self, from: AccountId, recipient: AccountId, amount: Balance) -> Result<(), Error> {
+            self.do_transfer(from, self.env().account_id(), amount)?;
+            let pending = self
+                .pending_withdrawal(recipient)
+                .checked_add(amount)
+                .ok_or(Error::Overflow)?;
+            self.pending_withdrawals.insert(recipient, &pending);
+            Ok(())
+        }
+
+        /// Recover the account that signed the Blake2x256 hash of `payload`
+        /// Accounts derive from ECDSA keys as the Blake2x256 hash of the compressed public key
+        fn recover_signer<T: scale::Encode>(&self, payload: &T, signature: &[u8; 65]) -> Result<AccountId, Error> {
+            let message_hash = self.env().hash_encoded::<Blake2x256, _>(payload);
+            let public_key = self
+                .env()
+                .ecdsa_recover(signature, &message_hash)
+                .map_err(|_| Error::InvalidSignature)?;
+            let signer = self.env().hash_bytes::<Blake2x256>(&public_key);
+            Ok(AccountId::from(signer))
+        }
+
+        /// Fold a merkle proof into the root it implies for `leaf`
+        fn merkle_root_of(&self, leaf: [u8; 32], proof: &[[u8; 32]]) -> [u8; 32] {
+            proof.iter().fold(leaf, |node, sibling| {
+                let mut pair = [0u8; 64];
+                let (first, second) = if node <= *sibling {
+                    (node, *sibling)
+                } else {
+                    (*sibling, node)
+                };
+                pair[..32].copy_from_slice(&first);
+                pair[32..].copy_from_slice(&second);
+                self.env().hash_bytes::<Blake2x256>(&pair)
+            })
+        }
+
+        /// Consume the expected nonce of an account
+        fn use_nonce(&mut self, owner: AccountId, nonce: u64) -> Result<(), Error> {
+            let expected = self.nonces(owner);
+            if nonce != expected {
+                return Err(Error::InvalidNonce);
+            }
+            let next = expected.checked_add(1).ok_or(Error::Overflow)?;
+            self.nonces.insert(owner, &next);
+            Ok(())
+        }
+
+        /// Whether an account sent a transfer less than the cooldown ago, the owner is exempt
+        fn is_cooling_down(&self, account: AccountId) -> bool {
+            if self.transfer_cooldown_secs == 0 || account == self.owner || self.batching.get().unwrap_or(false) {
+                return false;
+            }
+            let Some(last) = self.last_transfer_at.get(account) else {
+                return false;
+            };
+            // Block timestamps are in milliseconds
+            let cooldown = self.transfer_cooldown_secs.saturating_mul(1000);
+            self.env().block_timestamp() < last.saturating_add(cooldown)
+        }
+
+        /// Remember when an account last sent a transfer, only while a cooldown is configured
+        fn record_transfer_time(&mut self, account: AccountId) {
+            if self.transfer_cooldown_secs > 0 && account != self.owner {
+                self.last_transfer_at.insert(account, &self.env().block_timestamp());
+            }
+        }
+
+        /// Whether the blacklist prevents an address from sending tokens
+        fn is_send_blocked(&self, address: AccountId) -> bool {
+            self.blacklist.get(address).is_some_and(|mode| mode.block_send)
+        }
+
+        /// Ask the compliance registry whether `from` may send to `to`, denying if the call fails
+        fn registry_allows(&self, registry: AccountId, from: AccountId, to: AccountId) -> bool {
+            let result = build_call::<Environment>()
+                .call(registry)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("is_allowed")))
+                        .push_arg(from)
+                        .push_arg(to),
+                )
+                .returns::<bool>()
+                .try_invoke();
+
+            matches!(result, Ok(Ok(true)))
+        }
+
+        /// Reject moving any tokens while the contract is emergency locked or paused
+        fn ensure_not_halted(&self) -> Result<(), Error> {
+            if self.emergency_locked {
+                return Err(Error::EmergencyLocked);
+            }
+            if self.is_paused() {
+                return Err(Error::ContractPaused);
+            }
+            Ok(())
+        }
+
+        /// Checks for crediting an account outside of a transfer, by a mint or a payout of the vault
+        fn ensure_can_receive(&self, to: AccountId) -> Result<(), Error> {
+            self.ensure_not_halted()?;
+            if self.is_receive_blocked(to) {
+                return Err(Error::AddressBlacklisted);
+            }
+            Ok(())
+        }
+
+        /// Checks for debiting an account outside of a transfer, by a burn
+        fn ensure_can_send(&self, from: AccountId) -> Result<(), Error> {
+            self.ensure_not_halted()?;
+            if self.is_send_blocked(from) {
+                return Err(Error::AddressBlacklisted);
+            }
+            Ok(())
+        }
+
+        /// Whether the blacklist prevents an address from receiving tokens
+        fn is_receive_blocked(&self, address: AccountId) -> bool {
+            self.blacklist.get(address).is_some_and(|mode| mode.block_receive)
+        }
+
+        /// Store a blacklist entry and emit `Blacklisted`, or clear it when no direction is blocked
+        fn set_blacklist_mode(&mut self, account: AccountId, mode: BlacklistMode) {
+            if mode.block_send || mode.block_receive {
+                self.blacklist.insert(account, &mode);
+                self.env().emit_event(Blacklisted {
+                    account,
+                    by: self.env().caller(),
+                });
+            } else {
+                self.unblacklist(account);
+            }
+        }
+
+        /// Clear a blacklist entry and emit `Unblacklisted` if the account was listed
+        fn unblacklist(&mut self, account: AccountId) {
+            if self.blacklist.contains(account) {
+                self.blacklist.remove(account);
+                self.env().emit_event(Unblacklisted {
+                    account,
+                    by: self.env().caller(),
+                });
+            }
+        }
+
+        /// Charge a mint against the minter's remaining cap, if it has one
+        fn use_minter_cap(&mut self, minter: AccountId, amount: Balance) -> Result<(), Error> {
+            if let Some(cap) = self.minter_caps.get(minter) {
+                let remaining = cap.checked_sub(amount).ok_or(Error::MintCapExceeded)?;
+                self.minter_caps.insert(minter, &remaining);
+            }
+            Ok(())
+        }
+
+        /// Reject a mint that would push a regular account over `max_wallet`
+        fn ensure_mint_within_max_wallet(&self, to: AccountId, amount: Balance) -> Result<(), Error> {
+            if to == self.owner || self.is_excluded(to) {
+                return Ok(());
+            }
+            if let Some(max) = self.max_wallet {
+                let resulting = self.balance_of(to).checked_add(amount).ok_or(Error::Overflow)?;
+                if resulting > max {
+                    return Err(Error::ExceedsMaxWallet);
+                }
+            }
+            Ok(())
+        }
+
+        /// Require the owner or a holder of `role` as caller
+        fn ensure_role(&self, role: Role) -> Result<(), Error> {
+            if self.is_renounced() {
+                return Err(Error::OwnershipRenounced);
+            }
+            let caller = self.env().caller();
+            if caller != self.owner && !self.has_role(role, caller) {
+                return Err(Error::MissingRole);
+            }
+            Ok(())
+        }
+
+        /// Require `role` as for `ensure_role`, for actions that go through proposals when multisig is enabled
+        fn ensure_direct_role(&self, role: Role) -> Result<(), Error> {
+            self.ensure_role(role)?;
+            if self.threshold > 0 {
+                return Err(Error::ProposalRequired);
+            }
+            Ok(())
+        }
+
+        /// Reject the owner calling a timelocked action directly, it must use `queue_action` while a delay is set
+        fn ensure_not_timelocked(&self) -> Result<(), Error> {
+            if self.timelock_delay_secs > 0 && self.env().caller() == self.owner {
+                return Err(Error::TimelockRequired);
+            }
+            Ok(())
+        }
+
+        /// Require the owner as caller, for actions that go through proposals when multisig is enabled
+        fn ensure_direct_owner(&self) -> Result<(), Error> {
+            if self.env().caller() != self.owner {
+                return Err(Error::NotOwner);
+            }
+            if self.threshold > 0 {
+                return Err(Error::ProposalRequired);
+            }
+            Ok(())
+        }
+
+        /// Whether ownership was renounced, disabling every administrative path
+        fn is_renounced(&self) -> bool {
+            self.owner == AccountId::from([0u8; 32])
+        }
+
+        /// Whether a cross-contract call made by this contract is in progress
+        fn is_locked(&self) -> bool {
+            self.locked.get().unwrap_or(false)
+        }
+
+        /// Notify the transfer hook, if any, holding the reentrancy lock for the duration of the call
+        fn call_transfer_hook(&mut self, from: AccountId, to: AccountId, value: Balance) -> Result<(), Error> {
+            let Some(hook) = self.transfer_hook else {
+                return Ok(());
+            };
+
+            self.locked.set(&true);
+            let result = build_call::<Environment>()
+                .call(hook)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("on_token_transfer")))
+                        .push_arg(from)
+                        .push_arg(to)
+                        .push_arg(value),
+                )
+                .returns::<()>()
+                .try_invoke();
+            self.locked.set(&false);
+
+            match result {
+                Ok(Ok(())) => Ok(()),
+                _ if self.hook_must_succeed => Err(Error::HookFailed),
+                _ => Ok(()),
+            }
+        }
+
+        /// Ask a receiving contract to accept tokens, holding the reentrancy lock for the duration of the call
+        fn call_transfer_receiver(
+            &mut self,
+            operator: AccountId,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+            data: Vec<u8>,
+        ) -> Result<(), Error> {
+            let selector = ink::selector_bytes!("on_transfer_received");
+
+            self.locked.set(&true);
+            let result = build_call::<Environment>()
+                .call(to)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selector))
+                        .push_arg(operator)
+                        .push_arg(from)
+                        .push_arg(value)
+                        .push_arg(data),
+                )
+                .returns::<[u8; 4]>()
+                .try_invoke();
+            self.locked.set(&false);
+
+            match result {
+                Ok(Ok(accepted)) if accepted == selector => Ok(()),
+                _ => Err(Error::ReceiverRejected),
+            }
+        }
+
+        /// Call the `PSP22Receiver::before_received` hook of a receiving contract
+        fn call_psp22_receiver(
+            &mut self,
+            operator: AccountId,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+            data: Vec<u8>,
+        ) -> Result<(), Error> {
+            self.locked.set(&true);
+            let result = build_call::<Environment>()
+                .call(to)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(ink::selector_bytes!("PSP22Receiver::before_received")))
+                        .push_arg(operator)
+                        .push_arg(from)
+                        .push_arg(value)
+                        .push_arg(data),
+                )
+                .returns::<Result<(), PSP22ReceiverError>>()
+                .try_invoke();
+            self.locked.set(&false);
+
+            match result {
+                Ok(Ok(Ok(()))) => Ok(()),
+                _ => Err(Error::ReceiverRejected),
+            }
+        }
+
+        /// Ask a contract receiving freshly minted tokens to accept them
+        fn call_token_received(&mut self, operator: AccountId, to: AccountId, value: Balance) -> Result<(), Error> {
+            let selector = ink::selector_bytes!("on_token_received");
+
+            self.locked.set(&true);
+            let result = build_call::<Environment>()
+                .call(to)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selector))
+                        .push_arg(operator)
+                        .push_arg(value),
+                )
+                .returns::<[u8; 4]>()
+                .try_invoke();
+            self.locked.set(&false);
+
+            match result {
+                Ok(Ok(accepted)) if accepted == selector => Ok(()),
+                _ => Err(Error::ReceiverRejected),
+            }
+        }
+
+        /// Write a balance, recording the previous value for the current snapshot first
+        /// and keeping the excluded balance sum in sync
+        fn set_balance(&mut self, account: AccountId, balance: Balance) -> Result<(), Error> {
+            let previous = self.balance_of(account);
+
+            // Offset the balance change so dividends already accrued to the account stay the same
+            if self.magnified_dividend_per_share > 0 && balance != previous {
+                let per_share = i128::try_from(self.magnified_dividend_per_share).map_err(|_| Error::Overflow)?;
+                let delta = i128::try_from(previous)
+                    .map_err(|_| Error::Overflow)?
+                    .checked_sub(i128::try_from(balance).map_err(|_| Error::Overflow)?)
+                    .ok_or(Error::Overflow)?;
+                let correction = per_share
+                    .checked_mul(delta)
+                    .and_then(|offset| self.dividend_corrections.get(account).unwrap_or(0).checked_add(offset))
+                    .ok_or(Error::Overflow)?;
+                self.dividend_corrections.insert(account, &correction);
+            }
+
+            let current = self.current_snapshot_id;
+            if current > 0 {
+                let mut snapshots = self.account_snapshots.get(account).unwrap_or_default();
+                if snapshots.last().is_none_or(|(id, _)| *id < current) {
+                    snapshots.push((current, previous));
+                    self.account_snapshots.insert(account, &snapshots);
+                }
+            }
+
+            if self.is_excluded(account) {
+                self.excluded_balance_sum = self
+                    .excluded_balance_sum
+                    .checked_sub(previous)
+                    .and_then(|sum| sum.checked_add(balance))
+                    .ok_or(Error::Overflow)?;
+            }
+
+            if previous == 0 && balance > 0 {
+                self.holder_index.insert(account, &self.holders.len());
+                self.holders.push(&account);
+            } else if previous > 0 && balance == 0 {
+                if let (Some(index), Some(last)) = (self.holder_index.take(account), self.holders.pop()) {
+                    if last != account {
+                        self.holders.set(index, &last);
+                        self.holder_index.insert(last, &index);
+                    }
+                }
+            }
+
+            if balance != previous {
+                let mut checkpoints = self.balance_checkpoints.get(account).unwrap_or_default();
+                Self::write_checkpoint(&mut checkpoints, self.env().block_number(), balance);
+                self.balance_checkpoints.insert(account, &checkpoints);
+            }
+
+            if let Some(delegatee) = self.delegates.get(account) {
+                if balance > previous {
+                    self.move_votes(delegatee, balance - previous, true)?;
+                } else {
+                    self.move_votes(delegatee, previous - balance, false)?;
+                }
+            }
+
+            self.balances.insert(account, &balance);
+            Ok(())
+        }
+
+        /// Create new tokens for an account without any policy checks and emit `Mint` and `Transfer`
+        fn mint_balance(&mut self, to: AccountId, amount: Balance) -> Result<(), Error> {
+            if amount == 0 {
+                return Err(Error::InvalidAmount);
+            }
+
+            let new_total_supply = self.total_supply.checked_add(amount).ok_or(Error::Overflow)?;
+            if self.max_supply.is_some_and(|max| new_total_supply > max) {
+                return Err(Error::CapExceeded);
+            }
+            let new_total_minted = self.total_minted.checked_add(amount).ok_or(Error::Overflow)?;
+            let new_balance = self.balance_of(to).checked_add(amount).ok_or(Error::Overflow)?;
+            self.set_balance(to, new_balance)?;
+            self.total_supply = new_total_supply;
+            self.total_minted = new_total_minted;
+            self.checkpoint_supply();
+
+            let at = self.env().block_timestamp();
+            self.last_mint_at.insert(to, &at);
+
+            let seq = self.next_event_seq();
+            self.env().emit_event(Mint {
+                to,
+                value: amount,
+                at,
+                seq,
+            });
+            let seq = self.next_event_seq();
+            self.env().emit_event(Transfer {
+                from: None,
+                to: Some(to),
+                value: amount,
+                seq,
+            });
+            #[cfg(test)]
+            debug_assert!(self.check_invariants());
+            Ok(())
+        }
+
+        /// Destroy tokens of an account without any policy checks and emit `Burn` and `Transfer`
+        fn burn_balance(&mut self, from: AccountId, amount: Balance) -> Result<(), Error> {
+            if amount == 0 {
+                return Err(Error::InvalidAmount);
+            }
+
+            let current_balance = self.balance_of(from);
+            if current_balance < amount {
+                return Err(Error::InsufficientBalance);
+            }
+
+            let new_total_supply = self.total_supply.checked_sub(amount).ok_or(Error::Overflow)?;
+            let new_total_burned = self.total_burned.checked_add(amount).ok_or(Error::Overflow)?;
+            self.set_balance(from, current_balance - amount)?;
+            self.total_supply = new_total_supply;
+            self.total_burned = new_total_burned;
+            self.checkpoint_supply();
+
+            let seq = self.next_event_seq();
+            self.env().emit_event(Burn {
+                from,
+                value: amount,
+                seq,
+            });
+            let seq = self.next_event_seq();
+            self.env().emit_event(Transfer {
+                from: Some(from),
+                to: None,
+                value: amount,
+                seq,
+            });
+            #[cfg(test)]
+            debug_assert!(self.check_invariants());
+            Ok(())
+        }
+
+        /// Move tokens between two accounts without any policy checks and emit a `Transfer`
+        fn move_balance(&mut self, from: AccountId, to: AccountId, amount: Balance) -> Result<(), Error> {
+            let from_balance = self.balance_of(from);
+            let new_from_balance = from_balance
+                .checked_sub(amount)
+                .ok_or(Error::InsufficientBalance)?;
+            self.set_balance(from, new_from_balance)?;
+
+            let to_balance = self.balance_of(to);
+            let new_to_balance = to_balance.checked_add(amount).ok_or(Error::Overflow)?;
+            self.set_balance(to, new_to_balance)?;
+
+            let seq = self.next_event_seq();
+            self.env().emit_event(Transfer {
+                from: Some(from),
+                to: Some(to),
+                value: amount,
+                seq,
+            });
+            #[cfg(test)]
+            debug_assert!(self.check_invariants());
+            Ok(())
+        }
+
+        /// Advance the sequence number shared by `Transfer`, `Mint` and `Burn` events
+        fn next_event_seq(&mut self) -> u64 {
+            self.event_seq = self.event_seq.saturating_add(1);
+            self.event_seq
+        }
+
+        /// Verify the supply bookkeeping is consistent, checked after every balance change in tests
+        /// The balances of all holders must add up to `total_supply`, and those of excluded holders
+        /// to `excluded_balance_sum`
+        #[cfg(test)]
+        fn check_invariants(&self) -> bool {
+            let mut balance_sum: Balance = 0;
+            let mut excluded_sum: Balance = 0;
+            for index in 0..self.holders.len() {
+                let Some(holder) = self.holders.get(index) else {
+                    return false;
+                };
+                let balance = self.balance_of(holder);
+                balance_sum = balance_sum.saturating_add(balance);
+                if self.is_excluded(holder) {
+                    excluded_sum = excluded_sum.saturating_add(balance);
+                }
+            }
+
+            let minted_minus_burned = self.total_minted.checked_sub(self.total_burned);
+            balance_sum == self.total_supply
+                && excluded_sum == self.excluded_balance_sum
+                && minted_minus_burned == Some(self.total_supply)
+        }
+
+        /// Portion of `amount` given in basis points, rounded as requested
+        /// `bps` is at most 10000, so the result never exceeds `amount`
+        fn bps_of(amount: Balance, bps: u16, rounding: RoundingMode) -> Result<Balance, Error> {
+            let scaled = amount.checked_mul(Balance::from(bps)).ok_or(Error::Overflow)?;
+            let bias = match rounding {
+                RoundingMode::Down => 0,
+                RoundingMode::Up => 9_999,
+                RoundingMode::Nearest => 5_000,
+            };
+            scaled
+                .checked_add(bias)
+                .map(|biased| biased / 10_000)
+                .ok_or(Error::Overflow)
+        }
+
+        /// Amount of a schedule unlocked at `now`, growing linearly from `start` over `duration`
+        /// Nothing is unlocked before the cliff has passed
+        fn vested_at(schedule: &VestingSchedule, now: u64) -> Result<Balance, Error> {
+            if schedule.revoked {
+                return Ok(schedule.total);
+            }
+            if now <= schedule.start {
+                return Ok(0);
+            }
+            let elapsed = now - schedule.start;
+            if elapsed < schedule.cliff {
+                return Ok(0);
+            }
+            if elapsed >= schedule.duration {
+                return Ok(schedule.total);
+            }
+            schedule
+                .total
+                .checked_mul(elapsed as Balance)
+                .map(|scaled| scaled / schedule.duration as Balance)
+                .ok_or(Error::Overflow)
+        }
+
+        /// Reward per staked token accrued up to the current block, and the part of the pool it credits
+        /// Only the credited part leaves the pool, what `REWARD_PRECISION` rounds away stays for later blocks
+        fn pending_reward_per_token(&self) -> Result<(Balance, Balance), Error> {
+            if self.total_staked == 0 {
+                return Ok((self.reward_per_token, 0));
+            }
+            let blocks = self
+                .env()
+                .block_number()
+                .checked_sub(self.last_reward_block)
+                .ok_or(Error::Overflow)?;
+            let paid_out = Balance::from(blocks)
+                .checked_mul(self.reward_rate)
+                .ok_or(Error::Overflow)?
+                .min(self.reward_pool);
+            let accrued = paid_out
+                .checked_mul(REWARD_PRECISION)
+                .ok_or(Error::Overflow)?
+                .checked_div(self.total_staked)
+                .ok_or(Error::Overflow)?;
+            let credited = accrued
+                .checked_mul(self.total_staked)
+                .ok_or(Error::Overflow)?
+                / REWARD_PRECISION;
+            let reward_per_token = self.reward_per_token.checked_add(accrued).ok_or(Error::Overflow)?;
+            Ok((reward_per_token, credited))
+        }
+
+        /// Rewards of an account at the given reward per staked token
+        fn earned_with(&self, account: AccountId, reward_per_token: Balance) -> Result<Balance, Error> {
+            let paid = self.reward_per_token_paid.get(account).unwrap_or(0);
+            let accrued = self
+                .staked
+                .get(account)
+                .unwrap_or(0)
+                .checked_mul(reward_per_token.checked_sub(paid).ok_or(Error::Overflow)?)
+                .ok_or(Error::Overflow)?
+                / REWARD_PRECISION;
+            self.staking_rewards
+                .get(account)
+                .unwrap_or(0)
+                .checked_add(accrued)
+                .ok_or(Error::Overflow)
+        }
+
+        /// Accrue staking rewards up to the current block, checkpointing `account` if given
+        /// While the reward due rounds down to nothing, the blocks are left to accumulate instead
+        fn update_staking_rewards(&mut self, account: Option<AccountId>) -> Result<(), Error> {
+            let (reward_per_token, credited) = self.pending_reward_per_token()?;
+            self.reward_per_token = reward_per_token;
+            self.reward_pool = self.reward_pool.checked_sub(credited).ok_or(Error::Overflow)?;
+            if credited > 0 || self.reward_rate == 0 || self.reward_pool == 0 {
+                self.last_reward_block = self.env().block_number();
+            }
+
+            if let Some(account) = account {
+                let earned = self.earned_with(account, reward_per_token)?;
+                self.staking_rewards.insert(account, &earned);
+                self.reward_per_token_paid.insert(account, &reward_per_token);
+            }
+            Ok(())
+        }
+
+        /// Add or remove an account from the circulating supply exclusions, keeping `excluded_balance_sum` in step
+        fn set_supply_exclusion(&mut self, account: AccountId, excluded: bool) -> Result<(), Error> {
+            if excluded == self.is_excluded(account) {
+                return Ok(());
+            }
+
+            let balance = self.balance_of(account);
+            if excluded {
+                self.excluded_from_circulating.insert(account, &true);
+                self.excluded_balance_sum = self
+                    .excluded_balance_sum
+                    .checked_add(balance)
+                    .ok_or(Error::Overflow)?;
+            } else {
+                self.excluded_from_circulating.remove(account);
+                self.excluded_balance_sum = self
+                    .excluded_balance_sum
+                    .checked_sub(balance)
+                    .ok_or(Error::Overflow)?;
+            }
+            Ok(())
+        }
+
+        /// Add or remove votes of a delegatee and checkpoint the result at the current block
+        fn move_votes(&mut self, delegatee: AccountId, amount: Balance, add: bool) -> Result<(), Error> {
+            if amount == 0 {
+                return Ok(());
+            }
+            let mut checkpoints = self.vote_checkpoints.get(delegatee).unwrap_or_default();
+            let previous = checkpoints.last().map(|(_, votes)| *votes).unwrap_or(0);
+            let votes = if add {
+                previous.checked_add(amount)
+            } else {
+                previous.checked_sub(amount)
+            }
+            .ok_or(Error::Overflow)?;
+
+            Self::write_checkpoint(&mut checkpoints, self.env().block_number(), votes);
+            self.vote_checkpoints.insert(delegatee, &checkpoints);
+
+            self.env().emit_event(DelegateVotesChanged {
+                delegate: delegatee,
+                previous_votes: previous,
+                new_votes: votes,
+            });
+            Ok(())
+        }
+
+        /// Record `amount` from `block` onwards, overwriting a checkpoint already written in that block
+        fn write_checkpoint(checkpoints: &mut Vec<Checkpoint>, block: u32, amount: Balance) {
+            match checkpoints.last_mut() {
+                Some(last) if last.0 == block => last.1 = amount,
+                _ => checkpoints.push((block, amount)),
+            }
+        }
+
+        /// Record the current total supply from the current block onwards
+        fn checkpoint_supply(&mut self) {
+            let block = self.env().block_number();
+            let checkpoint = (block, self.total_supply);
+            let last = self.supply_checkpoints.len().checked_sub(1);
+            match last.and_then(|index| self.supply_checkpoints.get(index).map(|entry| (index, entry))) {
+                Some((index, (from_block, _))) if from_block == block => {
+                    self.supply_checkpoints.set(index, &checkpoint);
+                }
+                _ => self.supply_checkpoints.push(&checkpoint),
+            }
+        }
+
+        /// Amount recorded at the end of `block`, 0 before the first checkpoint
+        fn checkpoint_at(checkpoints: &[Checkpoint], block: u32) -> Balance {
+            let index = checkpoints.partition_point(|(from_block, _)| *from_block <= block);
+            index
+                .checked_sub(1)
+                .and_then(|index| checkpoints.get(index))
+                .map(|(_, amount)| *amount)
+                .unwrap_or(0)
+        }
+
+        /// Dividends ever accrued to an account, withdrawn or not
+        fn accumulative_dividend_of(&self, account: AccountId) -> Result<Balance, Error> {
+            let magnified = self
+                .magnified_dividend_per_share
+                .checked_mul(self.balance_of(account))
+                .ok_or(Error::Overflow)?;
+            let corrected = i128::try_from(magnified)
+                .map_err(|_| Error::Overflow)?
+                .checked_add(self.dividend_corrections.get(account).unwrap_or(0))
+                .ok_or(Error::Overflow)?;
+            Ok(Balance::try_from(corrected).map_err(|_| Error::Overflow)? / REWARD_PRECISION)
+        }
+
+        /// Dividends accrued to an account and not yet withdrawn
+        fn dividend_due(&self, account: AccountId) -> Result<Balance, Error> {
+            self.accumulative_dividend_of(account)?
+                .checked_sub(self.withdrawn_dividend_of(account))
+                .ok_or(Error::Overflow)
+        }
+
+        /// Amount of a stream paid out at `now`, growing linearly from `start` to `stop`
+        fn streamed_at(stream: &Stream, now: u64) -> Result<Balance, Error> {
+            if now <= stream.start {
+                return Ok(0);
+            }
+            if now >= stream.stop {
+                return Ok(stream.deposit);
+            }
+            stream
+                .deposit
+                .checked_mul((now - stream.start) as Balance)
+                .map(|scaled| scaled / (stream.stop - stream.start) as Balance)
+                .ok_or(Error::Overflow)
+        }
+
+        /// Reject allowances above the configured cap
+        fn ensure_allowance_within_cap(&self, amount: Balance) -> Result<(), Error> {
+            match self.max_allowance {
+                Some(cap) if amount > cap => Err(Error::AllowanceTooHigh),
+                _ => Ok(()),
+            }
+        }
+
+        /// Set an allowance within the configured cap and emit an `Approval`
+        /// Every approval path goes through here, so approvals paused with `pause_approvals` stay paused everywhere
+        fn approve_spender(&mut self, owner: AccountId, spender: AccountId, amount: Balance) -> Result<(), Error> {
+            if self.is_paused() && self.pause_approvals {
+                return Err(Error::ContractPaused);
+            }
+            self.ensure_allowance_within_cap(amount)?;
+            self.set_allowance(owner, spender, amount);
+            self.allowed_recipients.remove((owner, spender));
+
+            self.env().emit_event(Approval {
+                owner,
+                spender,
+                value: amount,
+            });
+
+            Ok(())
+        }
+
+        /// Change an existing allowance, keeping its recipient restriction
+        fn adjust_allowance(&mut self, owner: AccountId, spender: AccountId, amount: Balance) -> Result<(), Error> {
+            let restriction = self.allowed_recipients.get((owner, spender));
+            self.approve_spender(owner, spender, amount)?;
+            if let Some(allowed_recipient) = restriction.filter(|_| amount > 0) {
+                self.allowed_recipients.insert((owner, spender), &allowed_recipient);
+            }
+            Ok(())
+        }
+
+        /// Store an allowance and keep the owner's spender list in sync
+        /// Zero allowances are removed from storage rather than stored
+        fn set_allowance(&mut self, owner: AccountId, spender: AccountId, amount: Balance) {
+            if amount == 0 {
+                self.allowances.remove((owner, spender));
+                self.allowed_recipients.remove((owner, spender));
+            } else {
+                self.allowances.insert((owner, spender), &amount);
+            }
+
+            let mut spenders = self.spenders.get(owner).unwrap_or_default();
+            match spenders.iter().position(|s| *s == spender) {
+                None if amount > 0 => spenders.push(spender),
+                Some(index) if amount == 0 => {
+                    spenders.swap_remove(index);
+                }
+                _ => return,
+            }
+
+            if spenders.is_empty() {
+                self.spenders.remove(owner);
+            } else {
+                self.spenders.insert(owner, &spenders);
+            }
+        }
+    }
+
+    impl PSP22 for SimpleToken {
+        #[ink(message)]
+        fn total_supply(&self) -> Balance {
+            self.total_supply
+        }
+
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> Balance {
+            self.balances.get(owner).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            self.allowances.get((owner, spender)).unwrap_or(0)
+        }
+
+        /// `data` is accepted for compatibility and not interpreted
+        #[ink(message)]
+        fn transfer(&mut self, to: AccountId, value: Balance, _data: Vec<u8>) -> Result<(), PSP22Error> {
+            let caller = self.env().caller();
+            self.do_transfer(caller, to, value)?;
+            Ok(())
+        }
+
+        /// `data` is accepted for compatibility and not interpreted
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            let caller = self.env().caller();
+            let allowance = self.allowances.get((from, caller)).unwrap_or(0);
+            
+            if allowance < value {
+                return Err(PSP22Error::InsufficientAllowance);
+            }
+
+            if self.allowed_recipients.get((from, caller)).is_some_and(|allowed| allowed != to) {
+                return Err(Error::RecipientNotAllowed.into());
+            }
+
+            // Update the allowance before the transfer hook is called,
+            // a `Balance::MAX` allowance is infinite and never decremented
+            if allowance != Balance::MAX {
+                let new_allowance = allowance.checked_sub(value).ok_or(Error::Overflow)?;
+                self.set_allowance(from, caller, new_allowance);
+                self.env().emit_event(Approval {
+                    owner: from,
+                    spender: caller,
+                    value: new_allowance,
+                });
+            }
+
+            self.do_transfer(from, to, value)?;
+            Ok(())
+        }
+
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: Balance) -> Result<(), PSP22Error> {
+            let owner = self.env().caller();
+            self.approve_spender(owner, spender, value)?;
+            Ok(())
+        }
+
+        /// Saturates at `Balance::MAX`, so an infinite allowance stays infinite
+        /// Keeps any recipient restriction of the allowance
+        #[ink(message)]
+        fn increase_allowance(&mut self, spender: AccountId, delta_value: Balance) -> Result<(), PSP22Error> {
+            let owner = self.env().caller();
+            let value = self.allowance(owner, spender).saturating_add(delta_value);
+            self.adjust_allowance(owner, spender, value)?;
+            Ok(())
+        }
+
+        /// Fails with `InsufficientAllowance` rather than going below zero
+        /// Keeps any recipient restriction of the allowance
+        #[ink(message)]
+        fn decrease_allowance(&mut self, spender: AccountId, delta_value: Balance) -> Result<(), PSP22Error> {
+            let owner = self.env().caller();
+            let value = self
+                .allowance(owner, spender)
+                .checked_sub(delta_value)
+                .ok_or(PSP22Error::InsufficientAllowance)?;
+            self.adjust_allowance(owner, spender, value)?;
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::test;
+
+        type Env = ink::env::DefaultEnvironment;
+
+        fn accounts() -> test::DefaultAccounts<Env> {
+            test::default_accounts::<Env>()
+        }
+
+        /// Decode the recorded events of type `T`, in emission order
+        fn events<T: ink::env::Event + scale::Decode>() -> Vec<T> {
+            let signature = T::SIGNATURE_TOPIC.map(|topic| topic.to_vec());
+            test::recorded_events()
+                .filter(|event| event.topics.first() == signature.as_ref())
+                .map(|event| T::decode(&mut &event.data[..]).expect("recorded event decodes"))
+                .collect()
+        }
+
+        fn set_caller(account: AccountId) {
+            test::set_caller::<Env>(account);
+        }
+
+        /// Account of the contract under test, kept apart from the default accounts
+        fn contract_id() -> AccountId {
+            AccountId::from([0xC0; 32])
+        }
+
+        /// Deploy a token owned by alice, the default caller, at `contract_id`
+        fn new_token() -> SimpleToken {
+            test::set_callee::<Env>(contract_id());
+            SimpleToken::new()
+        }
+
+        #[ink::test]
+        fn burn_emits_burn_and_transfer_events() {
+            let accounts = accounts();
+            let mut token = SimpleToken::new();
+            token.mint(accounts.alice, 100).unwrap();
+
+            token.burn(40).unwrap();
+
+            let burns = events::<Burn>();
+            assert_eq!(burns.len(), 1);
+            assert_eq!((burns[0].from, burns[0].value), (accounts.alice, 40));
+            let transfers = events::<Transfer>();
+            assert_eq!(transfers.len(), 2);
+            assert_eq!((transfers[1].from, transfers[1].to, transfers[1].value), (Some(accounts.alice), None, 40));
+        }
+
+        #[ink::test]
+        fn force_transfer_is_owner_only() {
+            let accounts = accounts();
+            let mut token = SimpleToken::new();
+            token.mint(accounts.bob, 100).unwrap();
+
+            set_caller(accounts.charlie);
+            assert_eq!(token.force_transfer(accounts.bob, accounts.charlie, 50), Err(Error::NotOwner));
+            assert_eq!(token.balance_of(accounts.bob), 100);
+        }
+
+        #[ink::test]
+        fn force_transfer_works_while_paused() {
+            let accounts = accounts();
+            let mut token = SimpleToken::new();
+            token.mint(accounts.bob, 100).unwrap();
+            token.pause().unwrap();
+
+            assert_eq!(token.force_transfer(accounts.bob, accounts.charlie, 60), Ok(()));
+            assert_eq!(token.balance_of(accounts.bob), 40);
+            assert_eq!(token.balance_of(accounts.charlie), 60);
+        }
+
+        #[ink::test]
+        fn terminate_is_owner_only() {
+            let accounts = accounts();
+            let mut token = SimpleToken::new();
+
+            set_caller(accounts.bob);
+            assert_eq!(token.terminate(accounts.bob), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn terminate_sends_native_balance_to_beneficiary() {
+            let accounts = accounts();
+            let mut token = SimpleToken::new();
+            test::set_account_balance::<Env>(test::callee::<Env>(), 1_000_000);
+
+            let should_terminate = move || {
+                let _ = token.terminate(accounts.django);
+            };
+            test::assert_contract_termination::<Env, _>(should_terminate, accounts.django, 1_000_000);
+        }
+
+        #[ink::test]
+        fn burn_rejects_supply_underflow() {
+            let accounts = accounts();
+            let mut token = SimpleToken::new();
+            token.mint(accounts.alice, 100).unwrap();
+            // Corrupt the bookkeeping so the balance exceeds the recorded supply
+            token.total_supply = 10;
+
+            assert_eq!(token.burn(50), Err(Error::Overflow));
+            assert_eq!(token.total_supply, 10);
+            assert_eq!(token.balance_of(accounts.alice), 100);
+        }
+
+        #[ink::test]
+        fn approvals_blocked_only_when_paused_with_flag() {
+            let accounts = accounts();
+            let mut token = SimpleToken::new();
+            let paused = Err(PSP22Error::Custom(String::from("ContractPaused")));
+
+            token.set_pause_approvals(true).unwrap();
+            assert_eq!(token.approve(accounts.bob, 10), Ok(()));
+
+            token.pause().unwrap();
+            assert_eq!(token.approve(accounts.bob, 20), paused);
+            assert_eq!(token.increase_allowance(accounts.bob, 5), paused);
+            assert_eq!(token.decrease_allowance(accounts.bob, 5), paused);
+            assert_eq!(
+                token.batch_approve(vec![accounts.charlie], vec![5]),
+                Err(Error::ContractPaused)
+            );
+            assert_eq!(token.allowance(accounts.alice, accounts.bob), 10);
+
+            token.set_pause_approvals(false).unwrap();
+            assert_eq!(token.approve(accounts.bob, 30), Ok(()));
+            assert_eq!(token.allowance(accounts.alice, accounts.bob), 30);
+        }
+
+        #[ink::test]
+        fn allowances_of_lists_nonzero_allowances() {
+            let accounts = accounts();
+            let mut token = SimpleToken::new();
+            token.approve(accounts.bob, 10).unwrap();
+            token.approve(accounts.charlie, 20).unwrap();
+            token.approve(accounts.django, 30).unwrap();
+
+            token.approve(accounts.bob, 0).unwrap();
+
+            // Revoking swaps the last spender into the freed slot
+            assert_eq!(
+                token.allowances_of(accounts.alice),
+                vec![(accounts.django, 30), (accounts.charlie, 20)]
+            );
+            assert_eq!(token.allowances_of(accounts.bob), vec![]);
+        }
+
+        #[ink::test]
+        fn min_transfer_rejects_smaller_amounts() {
+            let accounts = accounts();
+            let mut token = SimpleToken::new();
+            token.mint(accounts.alice, 100).unwrap();
+            token.set_min_transfer(10).unwrap();
+
+            assert_eq!(
+                PSP22::transfer(&mut token, accounts.bob, 9, Vec::new()),
+                Err(PSP22Error::Custom(String::from("BelowMinimum")))
+            );
+            assert_eq!(PSP22::transfer(&mut token, accounts.bob, 10, Vec::new()), Ok(()));
+            assert_eq!(PSP22::transfer(&mut token, accounts.bob, 11, Vec::new()), Ok(()));
+            assert_eq!(token.balance_of(accounts.bob), 21);
+        }
+
+        #[ink::test]
+        fn vesting_releases_linearly() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.alice, 1000).unwrap();
+            token.create_vesting(accounts.bob, 1000, 1000, 0, 10_000, false).unwrap();
+            assert_eq!(token.balance_of(contract_id()), 1000);
+
+            test::set_block_timestamp::<Env>(6000);
+            assert_eq!(token.releasable(accounts.bob), 500);
+            set_caller(accounts.bob);
+            assert_eq!(token.claim_vested(), Ok(500));
+            assert_eq!(token.balance_of(accounts.bob), 500);
+
+            test::set_block_timestamp::<Env>(20_000);
+            assert_eq!(token.claim_vested(), Ok(500));
+            assert_eq!(token.balance_of(accounts.bob), 1000);
+            assert_eq!(token.claim_vested(), Ok(0));
+        }
+
+        #[ink::test]
+        fn snapshot_rewards_are_pro_rata() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.bob, 300).unwrap();
+            token.mint(accounts.charlie, 100).unwrap();
+            let snapshot_id = token.snapshot().unwrap();
+            token.mint(accounts.alice, 1000).unwrap();
+            token.deposit_rewards(snapshot_id, 400).unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(token.claim_reward(snapshot_id), Ok(300));
+            assert_eq!(token.claim_reward(snapshot_id), Err(Error::AlreadyClaimed));
+            set_caller(accounts.charlie);
+            assert_eq!(token.claim_reward(snapshot_id), Ok(100));
+
+            assert_eq!(token.balance_of(accounts.bob), 600);
+            assert_eq!(token.balance_of(accounts.charlie), 200);
+            assert_eq!(token.balance_of(contract_id()), 0);
+        }
+
+        #[ink::test]
+        fn balances_of_keeps_input_order() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.bob, 20).unwrap();
+            token.mint(accounts.django, 40).unwrap();
+
+            assert_eq!(
+                token.balances_of(vec![accounts.django, accounts.charlie, accounts.bob, accounts.eve]),
+                vec![40, 0, 20, 0]
+            );
+            assert_eq!(token.balances_of(Vec::new()), Vec::<Balance>::new());
+        }
+
+        #[ink::test]
+        fn emergency_unlock_waits_for_the_delay() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.alice, 100).unwrap();
+            token.set_emergency_delay_secs(60).unwrap();
+            token.emergency_lock().unwrap();
+            assert_eq!(
+                PSP22::transfer(&mut token, accounts.bob, 10, Vec::new()),
+                Err(PSP22Error::Custom(String::from("EmergencyLocked")))
+            );
+
+            test::set_block_timestamp::<Env>(1_000);
+            token.request_emergency_unlock().unwrap();
+            assert_eq!(token.emergency_unlock_at(), Some(61_000));
+            test::set_block_timestamp::<Env>(60_999);
+            assert_eq!(token.execute_emergency_unlock(), Err(Error::UnlockNotReady));
+            assert!(token.is_emergency_locked());
+
+            test::set_block_timestamp::<Env>(61_000);
+            assert_eq!(token.execute_emergency_unlock(), Ok(()));
+            assert!(!token.is_emergency_locked());
+            assert_eq!(PSP22::transfer(&mut token, accounts.bob, 10, Vec::new()), Ok(()));
+        }
+
+        #[ink::test]
+        fn reports_contract_and_storage_versions() {
+            let token = new_token();
+            assert_eq!(token.version(), CONTRACT_VERSION);
+            assert_eq!(token.version(), 1);
+            assert_eq!(token.storage_version(), STORAGE_VERSION);
+        }
+
+        #[ink::test]
+        fn mint_rejects_zero_amount() {
+            let accounts = accounts();
+            let mut token = new_token();
+
+            assert_eq!(token.mint(accounts.bob, 0), Err(Error::InvalidAmount));
+            assert_eq!(token.total_supply(), 0);
+            assert_eq!(test::recorded_events().count(), 0);
+        }
+
+        #[ink::test]
+        fn allowance_cap_limits_approvals() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.set_max_allowance(Some(100)).unwrap();
+
+            assert_eq!(token.approve(accounts.bob, 99), Ok(()));
+            assert_eq!(token.approve(accounts.charlie, 100), Ok(()));
+            assert_eq!(
+                token.approve(accounts.django, 101),
+                Err(PSP22Error::Custom(String::from("AllowanceTooHigh")))
+            );
+
+            assert_eq!(token.allowance(accounts.alice, accounts.bob), 99);
+            assert_eq!(token.allowance(accounts.alice, accounts.charlie), 100);
+            assert_eq!(token.allowance(accounts.alice, accounts.django), 0);
+            let approvals = events::<Approval>();
+            assert_eq!(approvals.len(), 2);
+            assert_eq!(
+                (approvals[1].owner, approvals[1].spender, approvals[1].value),
+                (accounts.alice, accounts.charlie, 100)
+            );
+        }
+
+        #[ink::test]
+        fn can_transfer_reports_each_failure() {
+            let accounts = accounts();
+            let (bob, charlie) = (accounts.bob, accounts.charlie);
+            let mut token = new_token();
+            token.mint(bob, 100).unwrap();
+            assert_eq!(token.can_transfer(bob, charlie, 50), Ok(()));
+
+            token.locked.set(&true);
+            assert_eq!(token.can_transfer(bob, charlie, 50), Err(Error::ReentrantCall));
+            token.locked.set(&false);
+
+            token.emergency_lock().unwrap();
+            assert_eq!(token.can_transfer(bob, charlie, 50), Err(Error::EmergencyLocked));
+            token.request_emergency_unlock().unwrap();
+            token.execute_emergency_unlock().unwrap();
+
+            token.pause().unwrap();
+            assert_eq!(token.can_transfer(bob, charlie, 50), Err(Error::ContractPaused));
+            token.unpause().unwrap();
+
+            token.add_to_blacklist(charlie, None).unwrap();
+            assert_eq!(token.can_transfer(bob, charlie, 50), Err(Error::AddressBlacklisted));
+            token.remove_from_blacklist(charlie).unwrap();
+
+            assert_eq!(token.can_transfer(bob, charlie, 0), Err(Error::InvalidAmount));
+
+            token.set_min_transfer(60).unwrap();
+            assert_eq!(token.can_transfer(bob, charlie, 50), Err(Error::BelowMinimum));
+            token.set_min_transfer(0).unwrap();
+
+            assert_eq!(token.can_transfer(bob, charlie, 101), Err(Error::InsufficientBalance));
+
+            token.set_transfer_cooldown_secs(60).unwrap();
+            set_caller(bob);
+            PSP22::transfer(&mut token, accounts.django, 10, Vec::new()).unwrap();
+            set_caller(accounts.alice);
+            assert_eq!(token.can_transfer(bob, charlie, 50), Err(Error::CooldownActive));
+            token.set_transfer_cooldown_secs(0).unwrap();
+
+            token.set_max_tx_amount(Some(40)).unwrap();
+            assert_eq!(token.can_transfer(bob, charlie, 50), Err(Error::ExceedsMaxTx));
+            token.set_max_tx_amount(None).unwrap();
+
+            token.set_max_wallet(Some(40)).unwrap();
+            assert_eq!(token.can_transfer(bob, charlie, 50), Err(Error::ExceedsMaxWallet));
+            token.set_max_wallet(None).unwrap();
+
+            assert_eq!(token.can_transfer(bob, charlie, 50), Ok(()));
+            assert_eq!(token.balance_of(bob), 90);
+        }
+
+        #[ink::test]
+        fn batch_approve_sets_each_allowance() {
+            let accounts = accounts();
+            let mut token = new_token();
+
+            token
+                .batch_approve(vec![accounts.bob, accounts.charlie, accounts.django], vec![10, 20, 30])
+                .unwrap();
+
+            assert_eq!(token.allowance(accounts.alice, accounts.bob), 10);
+            assert_eq!(token.allowance(accounts.alice, accounts.charlie), 20);
+            assert_eq!(token.allowance(accounts.alice, accounts.django), 30);
+        }
+
+        #[ink::test]
+        fn batch_approve_rejects_length_mismatch() {
+            let accounts = accounts();
+            let mut token = new_token();
+
+            assert_eq!(
+                token.batch_approve(vec![accounts.bob, accounts.charlie], vec![10]),
+                Err(Error::LengthMismatch)
+            );
+            assert_eq!(token.allowance(accounts.alice, accounts.bob), 0);
+        }
+
+        #[ink::test]
+        fn zero_approval_removes_the_allowance_entry() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.approve(accounts.bob, 50).unwrap();
+            assert!(token.allowances.contains((accounts.alice, accounts.bob)));
+
+            token.approve(accounts.bob, 0).unwrap();
+
+            assert_eq!(token.allowance(accounts.alice, accounts.bob), 0);
+            assert!(!token.allowances.contains((accounts.alice, accounts.bob)));
+        }
+
+        #[ink::test]
+        fn constructor_caller_is_owner() {
+            let accounts = accounts();
+            let token = new_token();
+
+            assert_eq!(token.owner(), accounts.alice);
+            assert!(token.is_owner(accounts.alice));
+            assert!(!token.is_owner(accounts.eve));
+        }
+
+        #[ink::test]
+        fn mint_whole_scales_by_decimals() {
+            let accounts = accounts();
+            let mut token = SimpleToken::new_with_config(None, None, 18, 0, accounts.alice).unwrap();
+
+            token.mint_whole(accounts.bob, 5).unwrap();
+
+            assert_eq!(token.balance_of(accounts.bob), 5 * 10u128.pow(18));
+        }
+
+        #[ink::test]
+        fn receive_only_blacklist_still_allows_sending() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.bob, 100).unwrap();
+            let receive_only = BlacklistMode {
+                block_send: false,
+                block_receive: true,
+            };
+            token.add_to_blacklist(accounts.bob, Some(receive_only)).unwrap();
+
+            assert_eq!(
+                PSP22::transfer(&mut token, accounts.bob, 10, Vec::new()),
+                Err(PSP22Error::Custom(String::from("AddressBlacklisted")))
+            );
+            set_caller(accounts.bob);
+            assert_eq!(PSP22::transfer(&mut token, accounts.charlie, 30, Vec::new()), Ok(()));
+            assert_eq!(token.balance_of(accounts.charlie), 30);
+        }
+
+        #[ink::test]
+        fn only_bridges_deposit_and_withdraw() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.set_bridge(accounts.frank, true).unwrap();
+            assert!(token.is_bridge(accounts.frank));
+
+            set_caller(accounts.frank);
+            assert_eq!(token.deposit_for(accounts.bob, 100), Ok(()));
+            assert_eq!(token.withdraw_for(accounts.bob, 30), Ok(()));
+            assert_eq!(token.balance_of(accounts.bob), 70);
+            assert_eq!(token.total_supply(), 70);
+
+            set_caller(accounts.eve);
+            assert_eq!(token.deposit_for(accounts.eve, 100), Err(Error::NotAuthorized));
+            assert_eq!(token.withdraw_for(accounts.bob, 10), Err(Error::NotAuthorized));
+            assert_eq!(token.balance_of(accounts.bob), 70);
+        }
+
+        #[ink::test]
+        fn total_burned_tracks_burns() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.alice, 100).unwrap();
+            assert_eq!(token.total_burned(), 0);
+
+            token.burn(30).unwrap();
+            assert_eq!((token.total_burned(), token.total_supply()), (30, 70));
+            token.burn(20).unwrap();
+            assert_eq!((token.total_burned(), token.total_supply()), (50, 50));
+        }
+
+        #[ink::test]
+        fn blacklist_rejects_owner_and_zero_address() {
+            let accounts = accounts();
+            let mut token = new_token();
+
+            assert_eq!(token.add_to_blacklist(accounts.alice, None), Err(Error::InvalidAddress));
+            assert_eq!(
+                token.add_to_blacklist(AccountId::from([0u8; 32]), None),
+                Err(Error::InvalidAddress)
+            );
+            assert_eq!(token.add_to_blacklist(accounts.bob, None), Ok(()));
+
+            assert!(!token.is_blacklisted(accounts.alice));
+            assert!(!token.is_blacklisted(AccountId::from([0u8; 32])));
+            assert!(token.is_blacklisted(accounts.bob));
+        }
+
+        #[ink::test]
+        fn memo_is_carried_in_the_event() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.alice, 100).unwrap();
+
+            token.transfer_with_memo(accounts.bob, 25, b"invoice-42".to_vec()).unwrap();
+
+            let memos = events::<TransferWithMemo>();
+            assert_eq!(memos.len(), 1);
+            assert_eq!((memos[0].from, memos[0].to, memos[0].value), (accounts.alice, accounts.bob, 25));
+            assert_eq!(memos[0].memo, b"invoice-42".to_vec());
+        }
+
+        #[ink::test]
+        fn oversized_memo_is_rejected() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.alice, 100).unwrap();
+
+            assert_eq!(
+                token.transfer_with_memo(accounts.bob, 25, vec![0; MAX_MEMO_LEN + 1]),
+                Err(Error::MemoTooLong)
+            );
+            assert_eq!(token.transfer_with_memo(accounts.bob, 25, vec![0; MAX_MEMO_LEN]), Ok(()));
+            assert_eq!(token.balance_of(accounts.bob), 25);
+        }
+
+        /// Send `amount` of bob's tokens to `to` through `transfer`, `transfer_from` or `batch_transfer`
+        fn transfer_via(token: &mut SimpleToken, path: u8, to: AccountId, amount: Balance) -> Result<(), PSP22Error> {
+            let accounts = accounts();
+            let result = match path {
+                0 => {
+                    set_caller(accounts.bob);
+                    PSP22::transfer(token, to, amount, Vec::new())
+                }
+                1 => {
+                    set_caller(accounts.charlie);
+                    PSP22::transfer_from(token, accounts.bob, to, amount, Vec::new())
+                }
+                _ => {
+                    set_caller(accounts.bob);
+                    token.batch_transfer(vec![to], amount).map_err(PSP22Error::from)
+                }
+            };
+            set_caller(accounts.alice);
+            result
+        }
+
+        /// Check the outcome of the common transfer cases through one of the `transfer_via` paths
+        fn assert_common_transfer_cases(path: u8) {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.bob, 100).unwrap();
+            set_caller(accounts.bob);
+            token.approve(accounts.charlie, Balance::MAX).unwrap();
+            set_caller(accounts.alice);
+
+            assert_eq!(transfer_via(&mut token, path, accounts.django, 30), Ok(()));
+            assert_eq!(token.balance_of(accounts.bob), 70);
+            assert_eq!(token.balance_of(accounts.django), 30);
+            let transfer = events::<Transfer>().pop().unwrap();
+            assert_eq!(
+                (transfer.from, transfer.to, transfer.value),
+                (Some(accounts.bob), Some(accounts.django), 30)
+            );
+
+            assert_eq!(
+                transfer_via(&mut token, path, accounts.django, 0),
+                Err(PSP22Error::Custom(String::from("InvalidAmount")))
+            );
+            assert_eq!(
+                transfer_via(&mut token, path, accounts.django, 71),
+                Err(PSP22Error::InsufficientBalance)
+            );
+
+            token.add_to_blacklist(accounts.django, None).unwrap();
+            assert_eq!(
+                transfer_via(&mut token, path, accounts.django, 10),
+                Err(PSP22Error::Custom(String::from("AddressBlacklisted")))
+            );
+
+            token.pause().unwrap();
+            assert_eq!(
+                transfer_via(&mut token, path, accounts.eve, 10),
+                Err(PSP22Error::Custom(String::from("ContractPaused")))
+            );
+            assert_eq!(token.balance_of(accounts.bob), 70);
+        }
+
+        #[ink::test]
+        fn transfer_covers_common_cases() {
+            assert_common_transfer_cases(0);
+        }
+
+        #[ink::test]
+        fn transfer_from_covers_common_cases() {
+            assert_common_transfer_cases(1);
+        }
+
+        #[ink::test]
+        fn batch_transfer_covers_common_cases() {
+            assert_common_transfer_cases(2);
+        }
+
+        #[ink::test]
+        fn cooldown_blocks_quick_repeat_transfers() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.bob, 100).unwrap();
+            token.set_transfer_cooldown_secs(60).unwrap();
+
+            set_caller(accounts.bob);
+            test::set_block_timestamp::<Env>(1_000);
+            assert_eq!(PSP22::transfer(&mut token, accounts.charlie, 10, Vec::new()), Ok(()));
+            assert_eq!(
+                PSP22::transfer(&mut token, accounts.charlie, 10, Vec::new()),
+                Err(PSP22Error::Custom(String::from("CooldownActive")))
+            );
+
+            test::set_block_timestamp::<Env>(61_000);
+            assert_eq!(PSP22::transfer(&mut token, accounts.charlie, 10, Vec::new()), Ok(()));
+            assert_eq!(token.balance_of(accounts.charlie), 20);
+        }
+
+        #[ink::test]
+        fn cooldown_does_not_apply_to_the_owner() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.alice, 100).unwrap();
+            token.set_transfer_cooldown_secs(60).unwrap();
+
+            assert_eq!(PSP22::transfer(&mut token, accounts.charlie, 10, Vec::new()), Ok(()));
+            assert_eq!(PSP22::transfer(&mut token, accounts.charlie, 10, Vec::new()), Ok(()));
+        }
+
+        #[ink::test]
+        fn batch_transfer_counts_once_for_the_cooldown() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.bob, 100).unwrap();
+            token.set_transfer_cooldown_secs(60).unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(token.batch_transfer(vec![accounts.charlie, accounts.django], 10), Ok(()));
+            assert_eq!(token.batch_transfer(vec![accounts.eve], 10), Err(Error::CooldownActive));
+            assert_eq!(token.balance_of(accounts.django), 10);
+        }
+
+        #[ink::test]
+        fn transfer_limits_apply_to_regular_accounts() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.bob, 1000).unwrap();
+            token.set_max_tx_amount(Some(100)).unwrap();
+            token.set_max_wallet(Some(150)).unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                PSP22::transfer(&mut token, accounts.charlie, 101, Vec::new()),
+                Err(PSP22Error::Custom(String::from("ExceedsMaxTx")))
+            );
+            assert_eq!(PSP22::transfer(&mut token, accounts.charlie, 100, Vec::new()), Ok(()));
+            assert_eq!(
+                PSP22::transfer(&mut token, accounts.charlie, 51, Vec::new()),
+                Err(PSP22Error::Custom(String::from("ExceedsMaxWallet")))
+            );
+            assert_eq!(PSP22::transfer(&mut token, accounts.charlie, 50, Vec::new()), Ok(()));
+            assert_eq!(token.balance_of(accounts.charlie), 150);
+        }
+
+        #[ink::test]
+        fn transfer_limits_exempt_the_owner() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.alice, 1000).unwrap();
+            token.set_max_tx_amount(Some(100)).unwrap();
+            token.set_max_wallet(Some(150)).unwrap();
+
+            assert_eq!(PSP22::transfer(&mut token, accounts.charlie, 500, Vec::new()), Ok(()));
+            set_caller(accounts.charlie);
+            assert_eq!(PSP22::transfer(&mut token, accounts.alice, 100, Vec::new()), Ok(()));
+            assert_eq!(token.balance_of(accounts.alice), 600);
+        }
+
+        #[ink::test]
+        fn spendable_allowance_is_capped_by_balance() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.alice, 50).unwrap();
+            token.approve(accounts.bob, 80).unwrap();
+            token.approve(accounts.charlie, 20).unwrap();
+
+            assert_eq!(token.spendable_allowance(accounts.alice, accounts.bob), 50);
+            assert_eq!(token.spendable_allowance(accounts.alice, accounts.charlie), 20);
+        }
+
+        #[ink::test]
+        fn upgrade_is_owner_only() {
+            let accounts = accounts();
+            let mut token = new_token();
+
+            set_caller(accounts.bob);
+            assert_eq!(token.upgrade(Hash::from([0x11; 32])), Err(Error::NotOwner));
+        }
+
+        #[ink::test]
+        fn queued_mint_waits_for_the_timelock() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.set_timelock_delay_secs(60).unwrap();
+            assert_eq!(token.mint(accounts.bob, 100), Err(Error::TimelockRequired));
+
+            test::set_block_timestamp::<Env>(1_000);
+            let id = token
+                .queue_action(PendingAction::Mint {
+                    to: accounts.bob,
+                    amount: 100,
+                })
+                .unwrap();
+
+            test::set_block_timestamp::<Env>(60_999);
+            assert_eq!(token.execute_action(id), Err(Error::ActionNotReady));
+            assert_eq!(token.balance_of(accounts.bob), 0);
+
+            test::set_block_timestamp::<Env>(61_000);
+            assert_eq!(token.execute_action(id), Ok(()));
+            assert_eq!(token.balance_of(accounts.bob), 100);
+            assert_eq!(token.execute_action(id), Err(Error::ActionNotFound));
+        }
+
+        #[ink::test]
+        fn queued_mint_draws_down_the_minter_cap() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.set_minter_cap(accounts.alice, Some(150)).unwrap();
+            token.set_timelock_delay_secs(60).unwrap();
+
+            let first = token
+                .queue_action(PendingAction::Mint {
+                    to: accounts.bob,
+                    amount: 100,
+                })
+                .unwrap();
+            let second = token
+                .queue_action(PendingAction::Mint {
+                    to: accounts.bob,
+                    amount: 100,
+                })
+                .unwrap();
+
+            test::set_block_timestamp::<Env>(60_000);
+            assert_eq!(token.execute_action(first), Ok(()));
+            assert_eq!(token.minter_cap(accounts.alice), Some(50));
+            assert_eq!(token.execute_action(second), Err(Error::MintCapExceeded));
+            assert_eq!(token.balance_of(accounts.bob), 100);
+        }
+
+        #[ink::test]
+        fn queued_fee_change_waits_for_the_timelock() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.set_timelock_delay_secs(60).unwrap();
+            assert_eq!(token.set_fee(100, accounts.frank), Err(Error::TimelockRequired));
+            assert_eq!(token.set_timelock_delay_secs(0), Err(Error::TimelockRequired));
+
+            let id = token
+                .queue_action(PendingAction::SetFee {
+                    basis_points: 100,
+                    collector: accounts.frank,
+                })
+                .unwrap();
+            test::set_block_timestamp::<Env>(60_000);
+            assert_eq!(token.execute_action(id), Ok(()));
+
+            let tax = token.tax_distribution().unwrap();
+            assert_eq!((tax.treasury, tax.treasury_bps), (accounts.frank, 100));
+        }
+
+        #[ink::test]
+        fn batch_to_frozen_account_is_escrowed_until_withdrawn() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.bob, 100).unwrap();
+            token.set_escrow_blocked_transfers(true).unwrap();
+            token.add_to_blacklist(accounts.django, None).unwrap();
+
+            set_caller(accounts.bob);
+            token.batch_transfer(vec![accounts.charlie, accounts.django], 30).unwrap();
+            assert_eq!(token.balance_of(accounts.charlie), 30);
+            assert_eq!(token.balance_of(accounts.django), 0);
+            assert_eq!(token.pending_withdrawal(accounts.django), 30);
+            assert_eq!(token.balance_of(contract_id()), 30);
+
+            set_caller(accounts.django);
+            assert_eq!(token.withdraw_pending(), Err(Error::AddressBlacklisted));
+            set_caller(accounts.alice);
+            token.remove_from_blacklist(accounts.django).unwrap();
+            set_caller(accounts.django);
+            assert_eq!(token.withdraw_pending(), Ok(30));
+            assert_eq!(token.balance_of(accounts.django), 30);
+            assert_eq!(token.pending_withdrawal(accounts.django), 0);
+        }
+
+        #[ink::test]
+        fn batch_transfer_rejects_empty_recipients() {
+            let mut token = new_token();
+            token.mint(accounts().alice, 100).unwrap();
+
+            assert_eq!(token.batch_transfer(Vec::new(), 10), Err(Error::InvalidAmount));
+        }
+
+        #[ink::test]
+        fn batch_transfer_rejects_overflowing_total() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.alice, 100).unwrap();
+
+            assert_eq!(
+                token.batch_transfer(vec![accounts.bob, accounts.charlie], Balance::MAX / 2 + 1),
+                Err(Error::Overflow)
+            );
+            assert_eq!(token.balance_of(accounts.alice), 100);
+        }
+
+        /// Account controlled by the ECDSA key `secret`
+        fn signer_of(secret: [u8; 32]) -> AccountId {
+            use secp256k1::{PublicKey, SecretKey, SECP256K1};
+
+            let secret = SecretKey::from_slice(&secret).unwrap();
+            let public_key = PublicKey::from_secret_key(SECP256K1, &secret).serialize();
+            let mut signer = [0u8; 32];
+            ink::env::hash_bytes::<Blake2x256>(&public_key, &mut signer);
+            AccountId::from(signer)
+        }
+
+        /// Sign the Blake2x256 hash of the SCALE encoded `payload` with `secret`
+        fn sign<T: scale::Encode>(secret: [u8; 32], payload: &T) -> [u8; 65] {
+            use secp256k1::{Message, SecretKey, SECP256K1};
+
+            let secret = SecretKey::from_slice(&secret).unwrap();
+            let mut hash = [0u8; 32];
+            ink::env::hash_encoded::<Blake2x256, _>(payload, &mut hash);
+            let message = Message::from_digest_slice(&hash).unwrap();
+            let (recovery_id, compact) = SECP256K1
+                .sign_ecdsa_recoverable(&message, &secret)
+                .serialize_compact();
+            let mut signature = [0u8; 65];
+            signature[..64].copy_from_slice(&compact);
+            signature[64] = recovery_id.to_i32() as u8;
+            signature
+        }
+
+        #[ink::test]
+        fn signed_transfer_can_be_relayed_once() {
+            let accounts = accounts();
+            let mut token = new_token();
+            let deadline = 1_000;
+            let signer = signer_of([7; 32]);
+            let payload = (contract_id(), signer, accounts.bob, 40 as Balance, 0u64, deadline);
+            let signature = sign([7; 32], &payload);
+            token.mint(signer, 100).unwrap();
+
+            set_caller(accounts.charlie);
+            assert_eq!(
+                token.transfer_with_signature(signer, accounts.bob, 40, 0, deadline, signature),
+                Ok(())
+            );
+            assert_eq!(token.balance_of(signer), 60);
+            assert_eq!(token.balance_of(accounts.bob), 40);
+            assert_eq!(token.balance_of(accounts.charlie), 0);
+            assert_eq!(token.nonces(signer), 1);
+
+            assert_eq!(
+                token.transfer_with_signature(signer, accounts.bob, 40, 0, deadline, signature),
+                Err(Error::InvalidNonce)
+            );
+            assert_eq!(token.balance_of(signer), 60);
+        }
+
+        #[ink::test]
+        fn signed_transfer_rejects_wrong_signer() {
+            let accounts = accounts();
+            let mut token = new_token();
+            let payload = (contract_id(), accounts.alice, accounts.bob, 40 as Balance, 0u64, 1_000u64);
+            let signature = sign([7; 32], &payload);
+            token.mint(accounts.alice, 100).unwrap();
+
+            set_caller(accounts.charlie);
+            assert_eq!(
+                token.transfer_with_signature(accounts.alice, accounts.bob, 40, 0, 1_000, signature),
+                Err(Error::InvalidSignature)
+            );
+            assert_eq!(token.balance_of(accounts.alice), 100);
+        }
+
+        #[ink::test]
+        fn airdrop_claims_require_a_valid_proof() {
+            let accounts = accounts();
+            let mut token = new_token();
+            let mut bob_leaf = [0u8; 32];
+            ink::env::hash_encoded::<Blake2x256, _>(&(accounts.bob, 100 as Balance), &mut bob_leaf);
+            let mut charlie_leaf = [0u8; 32];
+            ink::env::hash_encoded::<Blake2x256, _>(&(accounts.charlie, 50 as Balance), &mut charlie_leaf);
+            let mut pair = [0u8; 64];
+            let (first, second) = if bob_leaf <= charlie_leaf {
+                (bob_leaf, charlie_leaf)
+            } else {
+                (charlie_leaf, bob_leaf)
+            };
+            pair[..32].copy_from_slice(&first);
+            pair[32..].copy_from_slice(&second);
+            let mut root = [0u8; 32];
+            ink::env::hash_bytes::<Blake2x256>(&pair, &mut root);
+            token.set_airdrop_root(root).unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(token.claim_airdrop(100, vec![bob_leaf]), Err(Error::InvalidProof));
+            assert_eq!(token.claim_airdrop(50, vec![charlie_leaf]), Err(Error::InvalidProof));
+            assert_eq!(token.claim_airdrop(100, vec![charlie_leaf]), Ok(()));
+            assert_eq!(token.balance_of(accounts.bob), 100);
+            assert_eq!(token.claim_airdrop(100, vec![charlie_leaf]), Err(Error::AlreadyClaimed));
+
+            set_caller(accounts.charlie);
+            assert_eq!(token.claim_airdrop(50, vec![bob_leaf]), Ok(()));
+            assert_eq!(token.total_supply(), 150);
+        }
+
+        #[ink::test]
+        fn minting_to_excluded_account_keeps_circulating_supply() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.bob, 100).unwrap();
+            token.add_supply_exclusion(accounts.alice).unwrap();
+
+            token.mint(accounts.alice, 500).unwrap();
+            assert_eq!(token.total_supply(), 600);
+            assert_eq!(token.circulating_supply(), Ok(100));
+
+            PSP22::transfer(&mut token, accounts.bob, 50, Vec::new()).unwrap();
+            assert_eq!(token.circulating_supply(), Ok(150));
+        }
+
+        #[ink::test]
+        fn transfer_and_approve_sets_nothing_when_transfer_fails() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.alice, 100).unwrap();
+
+            assert_eq!(
+                token.transfer_and_approve(accounts.bob, 200, accounts.charlie, 50),
+                Err(Error::InsufficientBalance)
+            );
+            assert_eq!(token.balance_of(accounts.bob), 0);
+            assert_eq!(token.allowance(accounts.alice, accounts.charlie), 0);
+
+            assert_eq!(token.transfer_and_approve(accounts.bob, 60, accounts.charlie, 50), Ok(()));
+            assert_eq!(token.balance_of(accounts.bob), 60);
+            assert_eq!(token.allowance(accounts.alice, accounts.charlie), 50);
+        }
+
+        #[ink::test]
+        fn invariants_hold_across_mint_transfer_and_burn() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.add_supply_exclusion(accounts.django).unwrap();
+
+            token.mint(accounts.bob, 100).unwrap();
+            assert!(token.check_invariants());
+
+            set_caller(accounts.bob);
+            PSP22::transfer(&mut token, accounts.charlie, 40, Vec::new()).unwrap();
+            assert!(token.check_invariants());
+            PSP22::transfer(&mut token, accounts.django, 25, Vec::new()).unwrap();
+            assert!(token.check_invariants());
+
+            token.burn(35).unwrap();
+            assert!(token.check_invariants());
+            assert_eq!(token.total_supply(), 65);
+        }
+
+        #[ink::test]
+        fn multisig_mint_executes_on_second_confirmation() {
+            let accounts = accounts();
+            test::set_callee::<Env>(contract_id());
+            let mut token =
+                SimpleToken::new_multisig(vec![accounts.alice, accounts.bob, accounts.charlie], 2).unwrap();
+
+            set_caller(accounts.django);
+            assert_eq!(
+                token.propose(Action::Mint { to: accounts.eve, amount: 100 }),
+                Err(Error::NotOwner)
+            );
+
+            set_caller(accounts.bob);
+            let id = token.propose(Action::Mint { to: accounts.eve, amount: 100 }).unwrap();
+            assert_eq!(token.confirm(id), Ok(()));
+            assert_eq!(token.confirm(id), Err(Error::AlreadyConfirmed));
+            assert_eq!(token.balance_of(accounts.eve), 0);
+
+            set_caller(accounts.charlie);
+            assert_eq!(token.confirm(id), Ok(()));
+            assert_eq!(token.balance_of(accounts.eve), 100);
+            assert!(token.proposal(id).unwrap().executed);
+
+            set_caller(accounts.alice);
+            assert_eq!(token.confirm(id), Err(Error::ProposalExecuted));
+            assert_eq!(token.total_supply(), 100);
+        }
+
+        #[ink::test]
+        fn multisig_unpause_needs_a_proposal() {
+            let accounts = accounts();
+            test::set_callee::<Env>(contract_id());
+            let mut token = SimpleToken::new_multisig(vec![accounts.alice, accounts.bob], 2).unwrap();
+
+            let id = token.propose(Action::Pause).unwrap();
+            token.confirm(id).unwrap();
+            set_caller(accounts.bob);
+            token.confirm(id).unwrap();
+            assert!(token.is_paused());
+
+            set_caller(accounts.alice);
+            assert_eq!(token.unpause(), Err(Error::ProposalRequired));
+            let id = token.propose(Action::Unpause).unwrap();
+            token.confirm(id).unwrap();
+            assert!(token.is_paused());
+            set_caller(accounts.bob);
+            token.confirm(id).unwrap();
+            assert!(!token.is_paused());
+        }
+
+        #[ink::test]
+        fn large_transfer_fires_only_above_threshold() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.alice, 1_000).unwrap();
+            token.set_large_transfer_threshold(100).unwrap();
+
+            PSP22::transfer(&mut token, accounts.bob, 100, Vec::new()).unwrap();
+            assert!(events::<LargeTransfer>().is_empty());
+
+            PSP22::transfer(&mut token, accounts.bob, 101, Vec::new()).unwrap();
+            let large = events::<LargeTransfer>();
+            assert_eq!(large.len(), 1);
+            assert_eq!((large[0].from, large[0].to, large[0].value), (accounts.alice, accounts.bob, 101));
+        }
+
+        #[ink::test]
+        fn contract_native_balance_reports_account_balance() {
+            let token = new_token();
+            test::set_account_balance::<Env>(contract_id(), 1_000_000);
+
+            assert_eq!(token.contract_native_balance(), 1_000_000);
+        }
+
+        #[ink::test]
+        fn restricted_allowance_only_pays_allowed_recipient() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.alice, 100).unwrap();
+            token.approve_restricted(accounts.bob, 50, accounts.charlie).unwrap();
+            assert_eq!(token.allowed_recipient(accounts.alice, accounts.bob), Some(accounts.charlie));
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                PSP22::transfer_from(&mut token, accounts.alice, accounts.django, 20, Vec::new()),
+                Err(PSP22Error::Custom(format!("{:?}", Error::RecipientNotAllowed)))
+            );
+            assert_eq!(
+                PSP22::transfer_from(&mut token, accounts.alice, accounts.charlie, 20, Vec::new()),
+                Ok(())
+            );
+            assert_eq!(token.balance_of(accounts.charlie), 20);
+            assert_eq!(token.allowance(accounts.alice, accounts.bob), 30);
+        }
+
+        #[ink::test]
+        fn batch_blacklist_skips_owner_and_zero_address() {
+            let accounts = accounts();
+            let mut token = new_token();
+            let zero = AccountId::from([0u8; 32]);
+
+            set_caller(accounts.bob);
+            assert_eq!(token.batch_add_to_blacklist(vec![accounts.charlie]), Err(Error::MissingRole));
+
+            set_caller(accounts.alice);
+            token
+                .batch_add_to_blacklist(vec![accounts.charlie, accounts.alice, zero, accounts.django])
+                .unwrap();
+            assert!(token.is_blacklisted(accounts.charlie));
+            assert!(token.is_blacklisted(accounts.django));
+            assert!(!token.is_blacklisted(accounts.alice));
+            assert!(!token.is_blacklisted(zero));
+        }
+
+        #[ink::test]
+        fn holders_are_paginated_and_pruned() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.bob, 10).unwrap();
+            token.mint(accounts.charlie, 20).unwrap();
+            token.mint(accounts.django, 30).unwrap();
+            assert_eq!(token.holder_count(), 3);
+
+            assert_eq!(token.holders(0, 2), vec![(accounts.bob, 10), (accounts.charlie, 20)]);
+            assert_eq!(token.holders(2, 2), vec![(accounts.django, 30)]);
+            assert!(token.holders(3, 2).is_empty());
+
+            set_caller(accounts.bob);
+            token.burn(10).unwrap();
+            assert_eq!(token.holder_count(), 2);
+            assert_eq!(token.holders(0, 10), vec![(accounts.django, 30), (accounts.charlie, 20)]);
+        }
+
+        #[ink::test]
+        fn burn_rate_burns_share_of_each_transfer() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.bob, 1_000).unwrap();
+            token.set_burn_rate(100).unwrap();
+
+            set_caller(accounts.bob);
+            PSP22::transfer(&mut token, accounts.charlie, 500, Vec::new()).unwrap();
+            assert_eq!(token.balance_of(accounts.bob), 500);
+            assert_eq!(token.balance_of(accounts.charlie), 495);
+            assert_eq!(token.total_supply(), 995);
+            assert_eq!(token.total_burned(), 5);
+        }
+
+        #[ink::test]
+        fn admin_burn_is_owner_only() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.bob, 100).unwrap();
+
+            set_caller(accounts.bob);
+            assert_eq!(token.admin_burn(accounts.bob, 40), Err(Error::NotOwner));
+
+            set_caller(accounts.alice);
+            assert_eq!(token.admin_burn(accounts.bob, 40), Ok(()));
+            assert_eq!(token.balance_of(accounts.bob), 60);
+            assert_eq!(token.total_supply(), 60);
+            assert_eq!(token.admin_burn(accounts.bob, 61), Err(Error::InsufficientBalance));
+        }
+
+        #[ink::test]
+        fn batch_transfer_receipt_reports_totals() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.alice, 100).unwrap();
+
+            let receipt = token
+                .batch_transfer_receipt(vec![accounts.bob, accounts.charlie, accounts.django], 20)
+                .unwrap();
+            assert_eq!(
+                receipt,
+                BatchReceipt {
+                    total_sent: 60,
+                    recipient_count: 3,
+                }
+            );
+            assert_eq!(token.balance_of(accounts.alice), 40);
+        }
+
+        #[ink::test]
+        fn mint_respects_max_wallet() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.set_max_wallet(Some(100)).unwrap();
+
+            assert_eq!(token.mint(accounts.bob, 100), Ok(()));
+            assert_eq!(token.mint(accounts.bob, 1), Err(Error::ExceedsMaxWallet));
+            assert_eq!(token.balance_of(accounts.bob), 100);
+
+            assert_eq!(token.mint(accounts.alice, 500), Ok(()));
+        }
+
+        #[ink::test]
+        fn multisig_mint_respects_max_wallet() {
+            let accounts = accounts();
+            test::set_callee::<Env>(contract_id());
+            let mut token = SimpleToken::new_multisig(vec![accounts.alice, accounts.bob], 1).unwrap();
+            token.set_max_wallet(Some(100)).unwrap();
+
+            let id = token.propose(Action::Mint { to: accounts.eve, amount: 101 }).unwrap();
+            assert_eq!(token.confirm(id), Err(Error::ExceedsMaxWallet));
+            assert_eq!(token.balance_of(accounts.eve), 0);
+        }
+
+        #[ink::test]
+        fn queued_mint_respects_max_wallet() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.set_max_wallet(Some(100)).unwrap();
+            token.set_timelock_delay_secs(60).unwrap();
+
+            let id = token
+                .queue_action(PendingAction::Mint {
+                    to: accounts.bob,
+                    amount: 101,
+                })
+                .unwrap();
+            test::set_block_timestamp::<Env>(60_000);
+            assert_eq!(token.execute_action(id), Err(Error::ExceedsMaxWallet));
+            assert_eq!(token.balance_of(accounts.bob), 0);
+        }
+
+        #[ink::test]
+        fn bridge_deposit_respects_max_wallet() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.set_max_wallet(Some(100)).unwrap();
+            token.set_bridge(accounts.frank, true).unwrap();
+
+            set_caller(accounts.frank);
+            assert_eq!(token.deposit_for(accounts.bob, 101), Err(Error::ExceedsMaxWallet));
+            assert_eq!(token.deposit_for(accounts.bob, 100), Ok(()));
+        }
+
+        #[ink::test]
+        fn timed_pause_resumes_automatically() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.alice, 100).unwrap();
+            token.pause_until(10_000).unwrap();
+
+            test::set_block_timestamp::<Env>(9_999);
+            assert!(token.is_paused());
+            assert_eq!(
+                PSP22::transfer(&mut token, accounts.bob, 10, Vec::new()),
+                Err(PSP22Error::Custom(format!("{:?}", Error::ContractPaused)))
+            );
+
+            test::set_block_timestamp::<Env>(10_000);
+            assert!(!token.is_paused());
+            assert_eq!(PSP22::transfer(&mut token, accounts.bob, 10, Vec::new()), Ok(()));
+        }
+
+        #[ink::test]
+        fn infinite_allowance_is_not_decremented() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.alice, 100).unwrap();
+            PSP22::approve(&mut token, accounts.bob, Balance::MAX).unwrap();
+            PSP22::approve(&mut token, accounts.charlie, 50).unwrap();
+
+            set_caller(accounts.bob);
+            PSP22::transfer_from(&mut token, accounts.alice, accounts.django, 30, Vec::new()).unwrap();
+            assert_eq!(token.allowance(accounts.alice, accounts.bob), Balance::MAX);
+
+            set_caller(accounts.charlie);
+            PSP22::transfer_from(&mut token, accounts.alice, accounts.django, 30, Vec::new()).unwrap();
+            assert_eq!(token.allowance(accounts.alice, accounts.charlie), 20);
+            assert_eq!(token.balance_of(accounts.django), 60);
+        }
+
+        #[ink::test]
+        fn mint_records_block_timestamp() {
+            let accounts = accounts();
+            let mut token = new_token();
+            assert_eq!(token.last_mint_at(accounts.bob), None);
+
+            test::set_block_timestamp::<Env>(42_000);
+            token.mint(accounts.bob, 100).unwrap();
+            assert_eq!(token.last_mint_at(accounts.bob), Some(42_000));
+            let mints = events::<Mint>();
+            assert_eq!(mints.len(), 1);
+            assert_eq!((mints[0].to, mints[0].value, mints[0].at), (accounts.bob, 100, 42_000));
+        }
+
+        #[ink::test]
+        fn available_balance_includes_releasable_vesting() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.alice, 1000).unwrap();
+            token.mint(accounts.bob, 50).unwrap();
+            token.create_vesting(accounts.bob, 1000, 0, 0, 10_000, false).unwrap();
+
+            test::set_block_timestamp::<Env>(2_500);
+            assert_eq!(token.releasable(accounts.bob), 250);
+            assert_eq!(token.balance_of(accounts.bob), 50);
+            assert_eq!(token.available_balance(accounts.bob), 300);
+
+            set_caller(accounts.bob);
+            token.claim_vested().unwrap();
+            assert_eq!(token.available_balance(accounts.bob), token.balance_of(accounts.bob));
+        }
+
+        #[ink::test]
+        fn recover_foreign_tokens_rejects_own_token() {
+            let accounts = accounts();
+            let mut token = new_token();
+
+            set_caller(accounts.bob);
+            assert_eq!(
+                token.recover_foreign_tokens(accounts.charlie, accounts.bob, 10),
+                Err(Error::NotOwner)
+            );
+
+            set_caller(accounts.alice);
+            assert_eq!(
+                token.recover_foreign_tokens(contract_id(), accounts.bob, 10),
+                Err(Error::InvalidAddress)
+            );
+        }
+
+        #[ink::test]
+        fn tax_is_split_between_treasury_reward_and_burn() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.bob, 1_000).unwrap();
+            token
+                .set_tax_distribution(100, 100, 100, accounts.frank, accounts.eve)
+                .unwrap();
+
+            set_caller(accounts.bob);
+            PSP22::transfer(&mut token, accounts.charlie, 1_000, Vec::new()).unwrap();
+            assert_eq!(token.balance_of(accounts.charlie), 970);
+            assert_eq!(token.balance_of(accounts.frank), 10);
+            assert_eq!(token.balance_of(accounts.eve), 10);
+            assert_eq!(token.total_supply(), 990);
+            assert_eq!(token.total_burned(), 10);
+        }
+
+        #[ink::test]
+        fn allowances_batch_reads_each_pair() {
+            let accounts = accounts();
+            let mut token = new_token();
+            PSP22::approve(&mut token, accounts.bob, 10).unwrap();
+            PSP22::approve(&mut token, accounts.charlie, 20).unwrap();
 
-    #[ink(event)]
-    pub struct Approval {
-        #[ink(topic)]
-        owner: AccountId,
-        #[ink(topic)]
-        spender: AccountId,
-        value: Balance,
-    }
+            let pairs: Vec<Allowance> = vec![
+                (accounts.alice, accounts.charlie),
+                (accounts.bob, accounts.alice),
+                (accounts.alice, accounts.bob),
+            ];
+            assert_eq!(token.allowances_batch(pairs), vec![20, 0, 10]);
+        }
 
-    /// Storage for allowances: (owner, spender) -> amount
-    type Allowance = (AccountId, AccountId);
+        #[ink::test]
+        fn force_revoke_allowance_is_owner_only() {
+            let accounts = accounts();
+            let mut token = new_token();
+            set_caller(accounts.bob);
+            PSP22::approve(&mut token, accounts.charlie, 50).unwrap();
 
-    #[ink(storage)]
-    pub struct SimpleToken {
-        /// Mapping from account to balance
-        balances: Mapping<AccountId, Balance>,
-        /// Contract owner
-        owner: AccountId,
-        /// Total token supply
-        total_supply: Balance,
-        /// Allowances for token transfers
-        allowances: Mapping<Allowance, Balance>,
-        /// Whether the contract is paused
-        paused: bool,
-        /// Blacklisted addresses
-        blacklist: Mapping<AccountId, bool>,
-    }
+            assert_eq!(
+                token.force_revoke_allowance(accounts.bob, accounts.charlie),
+                Err(Error::NotOwner)
+            );
 
-    impl SimpleToken {
-        #[ink(constructor)]
-        pub fn new() -> Self {
-            let caller = Self::env().caller();
-            Self {
-                balances: Mapping::default(),
-                owner: caller,
-                total_supply: 0,
-                allowances: Mapping::default(),
-                paused: false,
-                blacklist: Mapping::default(),
-            }
+            set_caller(accounts.alice);
+            assert_eq!(token.force_revoke_allowance(accounts.bob, accounts.charlie), Ok(()));
+            assert_eq!(token.allowance(accounts.bob, accounts.charlie), 0);
+            let revoked = events::<AllowanceForceRevoked>();
+            assert_eq!(revoked.len(), 1);
+            assert_eq!(
+                (revoked[0].owner, revoked[0].spender, revoked[0].by),
+                (accounts.bob, accounts.charlie, accounts.alice)
+            );
         }
 
-        #[ink(message)]
-        pub fn mint(&mut self, to: AccountId, amount: Balance) -> Result<(), Error> {
-            let caller = self.env().caller();
-            if caller != self.owner {
-                return Err(Error::NotOwner);
+        #[ink::test]
+        fn supply_stats_track_minted_and_burned() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.alice, 300).unwrap();
+            token.mint(accounts.bob, 200).unwrap();
+            token.burn(120).unwrap();
+            token.admin_burn(accounts.bob, 30).unwrap();
+
+            let stats = token.supply_stats();
+            assert_eq!(
+                stats,
+                SupplyStats {
+                    total_supply: 350,
+                    total_minted: 500,
+                    total_burned: 150,
+                }
+            );
+            assert_eq!(stats.total_minted - stats.total_burned, stats.total_supply);
+        }
+
+        #[ink::test]
+        fn fee_rounding_modes_round_uneven_fees() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.bob, 10_000).unwrap();
+            token.set_fee(100, accounts.frank).unwrap();
+
+            for (rounding, fee_of_149, fee_of_150) in [
+                (RoundingMode::Down, 1, 1),
+                (RoundingMode::Up, 2, 2),
+                (RoundingMode::Nearest, 1, 2),
+            ] {
+                set_caller(accounts.alice);
+                token.set_fee_rounding(rounding).unwrap();
+                set_caller(accounts.bob);
+                for (amount, fee) in [(149, fee_of_149), (150, fee_of_150)] {
+                    let collected = token.balance_of(accounts.frank);
+                    let received = token.balance_of(accounts.charlie);
+                    PSP22::transfer(&mut token, accounts.charlie, amount, Vec::new()).unwrap();
+                    assert_eq!(token.balance_of(accounts.frank) - collected, fee);
+                    assert_eq!(token.balance_of(accounts.charlie) - received, amount - fee);
+                }
             }
-            let current_balance = self.balances.get(&to).unwrap_or(0);
-            self.balances.insert(&to, &(current_balance + amount));
-            self.total_supply += amount;
+        }
 
-            self.env().emit_event(Mint { to, value: amount });
-            self.env().emit_event(Transfer {
-                from: None,
-                to: Some(to),
-                value: amount,
-            });
-            Ok(())
+        #[ink::test]
+        fn migrate_runs_once() {
+            let mut token = new_token();
+
+            set_caller(accounts().bob);
+            assert_eq!(token.migrate(), Err(Error::NotOwner));
+
+            set_caller(accounts().alice);
+            assert_eq!(token.migrate(), Ok(()));
+            assert!(token.is_migrated());
+            assert_eq!(token.storage_version(), STORAGE_VERSION);
+            assert_eq!(token.migrate(), Err(Error::AlreadyMigrated));
         }
 
-        #[ink(message)]
-        pub fn balance_of(&self, account: AccountId) -> Balance {
-            self.balances.get(&account).unwrap_or(0)
+        #[ink::test]
+        fn transfer_events_have_increasing_seq() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.alice, 100).unwrap();
+            token
+                .batch_transfer(vec![accounts.bob, accounts.charlie, accounts.django], 10)
+                .unwrap();
+
+            let transfers = events::<Transfer>();
+            assert_eq!(transfers.len(), 4);
+            assert!(transfers.windows(2).all(|pair| pair[0].seq < pair[1].seq));
         }
 
-        #[ink(message)]
-        pub fn transfer(&mut self, to: AccountId, amount: Balance) -> Result<(), Error> {
-            if self.paused {
-                return Err(Error::ContractPaused);
-            }
-            
-            let caller = self.env().caller();
-            
-            if self.blacklist.get(&caller).unwrap_or(false) || self.blacklist.get(&to).unwrap_or(false) {
-                return Err(Error::AddressBlacklisted);
-            }
-            
-            if amount == 0 {
-                return Err(Error::InvalidAmount);
-            }
-            
-            let caller_balance = self.balances.get(&caller).unwrap_or(0);
+        #[ink::test]
+        fn validate_batch_reports_each_entry() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.alice, 25).unwrap();
+            token.add_to_blacklist(accounts.charlie, None).unwrap();
 
-            if caller_balance < amount {
-                return Err(Error::InsufficientBalance);
-            }
+            let statuses = token.validate_batch(
+                vec![
+                    accounts.bob,
+                    accounts.charlie,
+                    AccountId::from([0u8; 32]),
+                    accounts.django,
+                    accounts.eve,
+                ],
+                10,
+            );
+            assert_eq!(
+                statuses,
+                vec![
+                    BatchEntryStatus::Ok,
+                    BatchEntryStatus::Blacklisted,
+                    BatchEntryStatus::ZeroAddress,
+                    BatchEntryStatus::Ok,
+                    BatchEntryStatus::InsufficientBalance,
+                ]
+            );
+        }
 
-            self.balances.insert(&caller, &(caller_balance - amount));
+        #[ink::test]
+        fn validate_batch_reports_limits_and_frozen_senders() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.bob, 100).unwrap();
+            token.set_max_tx_amount(Some(5)).unwrap();
 
-            let receiver_balance = self.balances.get(&to).unwrap_or(0);
-            self.balances.insert(&to, &(receiver_balance + amount));
+            set_caller(accounts.bob);
+            assert_eq!(
+                token.validate_batch(vec![accounts.charlie], 10),
+                vec![BatchEntryStatus::Rejected(Error::ExceedsMaxTx)]
+            );
 
-            self.env().emit_event(Transfer {
-                from: Some(caller),
-                to: Some(to),
-                value: amount,
-            });
-            Ok(())
+            set_caller(accounts.alice);
+            token.add_to_blacklist(accounts.bob, None).unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(token.validate_batch(vec![accounts.charlie], 5), vec![BatchEntryStatus::Frozen]);
+
+            set_caller(accounts.alice);
+            token.remove_from_blacklist(accounts.bob).unwrap();
+            token.pause().unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(token.validate_batch(vec![accounts.charlie], 5), vec![BatchEntryStatus::Frozen]);
         }
 
-        #[ink(message)]
-        pub fn total_supply(&self) -> Balance {
-            self.total_supply
+        #[ink::test]
+        fn new_with_config_mints_to_owner_and_stores_config() {
+            let accounts = accounts();
+            test::set_callee::<Env>(contract_id());
+            let mut token = SimpleToken::new_with_config(
+                Some(String::from("Token")),
+                Some(String::from("TKN")),
+                12,
+                1_000,
+                accounts.bob,
+            )
+            .unwrap();
+
+            assert_eq!(token.token_name(), Some(String::from("Token")));
+            assert_eq!(token.token_symbol(), Some(String::from("TKN")));
+            assert_eq!(token.decimals(), 12);
+            assert_eq!(token.owner(), accounts.bob);
+            assert_eq!(token.balance_of(accounts.bob), 1_000);
+            assert_eq!(token.total_supply(), 1_000);
+
+            assert_eq!(token.mint(accounts.alice, 10), Err(Error::MissingRole));
         }
 
-        
-        /// Approve a spender to transfer tokens on behalf of the caller
-        #[ink(message)]
-        pub fn approve(&mut self, spender: AccountId, amount: Balance) -> Result<(), Error> {
-            let owner = self.env().caller();
-            self.allowances.insert(&(owner, spender), &amount);
-            
-            self.env().emit_event(Approval {
-                owner,
-                spender,
-                value: amount,
-            });
-            
-            Ok(())
+        #[ink::test]
+        fn mint_and_burn_respect_pause_and_blacklist() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.bob, 100).unwrap();
+            token.add_to_blacklist(accounts.charlie, None).unwrap();
+            assert_eq!(token.mint(accounts.charlie, 10), Err(Error::AddressBlacklisted));
+
+            token.pause().unwrap();
+            assert_eq!(token.mint(accounts.bob, 10), Err(Error::ContractPaused));
+            set_caller(accounts.bob);
+            assert_eq!(token.burn(10), Err(Error::ContractPaused));
+
+            set_caller(accounts.alice);
+            assert_eq!(token.admin_burn(accounts.bob, 10), Ok(()));
+            assert_eq!(token.balance_of(accounts.bob), 90);
         }
-        
-        /// Get the allowance of a spender for an owner
-        #[ink(message)]
-        pub fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
-            self.allowances.get(&(owner, spender)).unwrap_or(0)
+
+        #[ink::test]
+        fn cancel_scheduled_waits_for_unpause() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.mint(accounts.bob, 100).unwrap();
+            set_caller(accounts.bob);
+            let id = token.schedule_transfer(accounts.charlie, 40, 10_000).unwrap();
+
+            set_caller(accounts.alice);
+            token.pause().unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(token.cancel_scheduled(id), Err(Error::ContractPaused));
+
+            set_caller(accounts.alice);
+            token.unpause().unwrap();
+            set_caller(accounts.bob);
+            assert_eq!(token.cancel_scheduled(id), Ok(()));
+            assert_eq!(token.balance_of(accounts.bob), 100);
         }
-        
-        /// Transfer tokens from one account to another using allowance
-        #[ink(message)]
-        pub fn transfer_from(
-            &mut self,
-            from: AccountId,
-            to: AccountId,
-            amount: Balance,
-        ) -> Result<(), Error> {
-            if self.paused {
-                return Err(Error::ContractPaused);
-            }
-            
-            if self.blacklist.get(&from).unwrap_or(false) || self.blacklist.get(&to).unwrap_or(false) {
-                return Err(Error::AddressBlacklisted);
-            }
-            
-            if amount == 0 {
-                return Err(Error::InvalidAmount);
-            }
-            
-            let caller = self.env().caller();
-            let allowance = self.allowances.get(&(from, caller)).unwrap_or(0);
-            
-            if allowance < amount {
-                return Err(Error::InsufficientAllowance);
-            }
-            
-            let from_balance = self.balances.get(&from).unwrap_or(0);
-            if from_balance < amount {
-                return Err(Error::InsufficientBalance);
-            }
-            
-            // Update the allowance
-            self.allowances.insert(&(from, caller), &(allowance - amount));
-            
-            // Update balances
-            self.balances.insert(&from, &(from_balance - amount));
-            let to_balance = self.balances.get(&to).unwrap_or(0);
-            self.balances.insert(&to, &(to_balance + amount));
-            
-            self.env().emit_event(Transfer {
-                from: Some(from),
-                to: Some(to),
-                value: amount,
-            });
-            
-            Ok(())
+
+        #[ink::test]
+        fn remaining_mintable_follows_max_supply() {
+            let mut token = new_token();
+            token.mint(accounts().bob, 40).unwrap();
+            assert_eq!(token.remaining_mintable(), Ok(None));
+
+            token.set_max_supply(100).unwrap();
+            assert_eq!(token.remaining_mintable(), Ok(Some(60)));
+            token.mint(accounts().bob, 60).unwrap();
+            assert_eq!(token.remaining_mintable(), Ok(Some(0)));
         }
-        
-        /// Pause all token transfers (only owner)
-        #[ink(message)]
-        pub fn pause(&mut self) -> Result<(), Error> {
-            if self.env().caller() != self.owner {
-                return Err(Error::NotOwner);
-            }
-            self.paused = true;
-            Ok(())
+
+        #[ink::test]
+        fn bridges_cannot_mint_after_renounce() {
+            let accounts = accounts();
+            let mut token = new_token();
+            token.set_bridge(accounts.frank, true).unwrap();
+            token.renounce_ownership().unwrap();
+
+            set_caller(accounts.frank);
+            assert_eq!(token.deposit_for(accounts.bob, 10), Err(Error::OwnershipRenounced));
+            assert_eq!(token.total_supply(), 0);
         }
 
-        /// Unpause token transfers (only owner)
-        #[ink(message)]
-        pub fn unpause(&mut self) -> Result<(), Error> {
-            if self.env().caller() != self.owner {
-                return Err(Error::NotOwner);
-            }
-            self.paused = false;
+        #[ink::test]
+        fn new_with_config_rejects_zero_owner() {
+            test::set_callee::<Env>(contract_id());
+            let token = SimpleToken::new_with_config(None, None, 18, 1_000, AccountId::from([0u8; 32]));
+            assert!(matches!(token, Err(Error::InvalidAddress)));
+
+            let token = SimpleToken::default();
+            assert_eq!(token.owner(), accounts().alice);
+            assert_eq!(token.total_supply(), 0);
+        }
+    }
+
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink_e2e::ContractsBackend;
+        use test_mock::{TestMock, TestMockRef};
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        #[ink_e2e::test]
+        async fn transfer_hook_is_called<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+            let alice = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+            let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let mut constructor = SimpleTokenRef::new();
+            let token = client
+                .instantiate("token_contract", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("token instantiate failed");
+            let mut token_call = token.call_builder::<SimpleToken>();
+            let mut constructor = TestMockRef::new(true);
+            let hook = client
+                .instantiate("test_mock", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("mock instantiate failed");
+            let hook_call = hook.call_builder::<TestMock>();
+
+            client
+                .call(&ink_e2e::alice(), &token_call.set_transfer_hook(Some(hook.account_id)))
+                .submit()
+                .await
+                .expect("set_transfer_hook failed");
+            client
+                .call(&ink_e2e::alice(), &token_call.mint(alice, 100))
+                .submit()
+                .await
+                .expect("mint failed");
+            client
+                .call(&ink_e2e::alice(), &token_call.transfer(bob, 40, Vec::new()))
+                .submit()
+                .await
+                .expect("transfer failed");
+
+            let calls = client.call(&ink_e2e::alice(), &hook_call.calls()).dry_run().await?;
+            assert_eq!(calls.return_value(), 1);
+            let last_transfer = client.call(&ink_e2e::alice(), &hook_call.last_transfer()).dry_run().await?;
+            assert_eq!(last_transfer.return_value(), Some((alice, bob, 40)));
             Ok(())
         }
 
-        /// Check if the contract is paused
-        #[ink(message)]
-        pub fn is_paused(&self) -> bool {
-            self.paused
+        #[ink_e2e::test]
+        async fn reverting_transfer_hook_fails_only_required_hooks<Client: E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            let alice = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+            let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let mut constructor = SimpleTokenRef::new();
+            let token = client
+                .instantiate("token_contract", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("token instantiate failed");
+            let mut token_call = token.call_builder::<SimpleToken>();
+            let mut constructor = TestMockRef::new(false);
+            let hook = client
+                .instantiate("test_mock", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("mock instantiate failed");
+
+            client
+                .call(&ink_e2e::alice(), &token_call.set_transfer_hook(Some(hook.account_id)))
+                .submit()
+                .await
+                .expect("set_transfer_hook failed");
+            client
+                .call(&ink_e2e::alice(), &token_call.mint(alice, 100))
+                .submit()
+                .await
+                .expect("mint failed");
+
+            // A failing hook is ignored unless it must succeed
+            client
+                .call(&ink_e2e::alice(), &token_call.transfer(bob, 40, Vec::new()))
+                .submit()
+                .await
+                .expect("transfer failed");
+
+            client
+                .call(&ink_e2e::alice(), &token_call.set_hook_must_succeed(true))
+                .submit()
+                .await
+                .expect("set_hook_must_succeed failed");
+            let transfer = client
+                .call(&ink_e2e::alice(), &token_call.transfer(bob, 40, Vec::new()))
+                .dry_run()
+                .await?;
+            assert_eq!(
+                transfer.return_value(),
+                Err(PSP22Error::Custom(String::from("HookFailed")))
+            );
+
+            let balance = client.call(&ink_e2e::alice(), &token_call.balance_of(bob)).dry_run().await?;
+            assert_eq!(balance.return_value(), 40);
+            Ok(())
         }
 
-        /// Add an address to the blacklist (only owner)
-        #[ink(message)]
-        pub fn add_to_blacklist(&mut self, address: AccountId) -> Result<(), Error> {
-            if self.env().caller() != self.owner {
-                return Err(Error::NotOwner);
-            }
-            self.blacklist.insert(&address, &true);
+        #[ink_e2e::test]
+        async fn transfer_and_call_needs_the_receiver_to_accept<Client: E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            let alice = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+            let mut constructor = SimpleTokenRef::new();
+            let token = client
+                .instantiate("token_contract", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("token instantiate failed");
+            let mut token_call = token.call_builder::<SimpleToken>();
+            let mut constructor = TestMockRef::new(true);
+            let accepting = client
+                .instantiate("test_mock", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("mock instantiate failed");
+            let accepting_call = accepting.call_builder::<TestMock>();
+            let mut constructor = TestMockRef::new(false);
+            let rejecting = client
+                .instantiate("test_mock", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("mock instantiate failed");
+
+            client
+                .call(&ink_e2e::alice(), &token_call.mint(alice, 100))
+                .submit()
+                .await
+                .expect("mint failed");
+            client
+                .call(
+                    &ink_e2e::alice(),
+                    &token_call.transfer_and_call(accepting.account_id, 40, Vec::new()),
+                )
+                .submit()
+                .await
+                .expect("transfer_and_call failed");
+            let rejected = client
+                .call(
+                    &ink_e2e::alice(),
+                    &token_call.transfer_and_call(rejecting.account_id, 40, Vec::new()),
+                )
+                .dry_run()
+                .await?;
+            assert_eq!(rejected.return_value(), Err(Error::ReceiverRejected));
+
+            let calls = client.call(&ink_e2e::alice(), &accepting_call.calls()).dry_run().await?;
+            assert_eq!(calls.return_value(), 1);
+            let accepted = client
+                .call(&ink_e2e::alice(), &token_call.balance_of(accepting.account_id))
+                .dry_run()
+                .await?;
+            assert_eq!(accepted.return_value(), 40);
+            let rejected = client
+                .call(&ink_e2e::alice(), &token_call.balance_of(rejecting.account_id))
+                .dry_run()
+                .await?;
+            assert_eq!(rejected.return_value(), 0);
             Ok(())
         }
 
-        /// Remove an address from the blacklist (only owner)
-        #[ink(message)]
-        pub fn remove_from_blacklist(&mut self, address: AccountId) -> Result<(), Error> {
-            if self.env().caller() != self.owner {
-                return Err(Error::NotOwner);
-            }
-            self.blacklist.remove(&address);
+        #[ink_e2e::test]
+        async fn upgrade_keeps_storage<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+            let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let mut constructor = SimpleTokenRef::new();
+            let token = client
+                .instantiate("token_contract", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("token instantiate failed");
+            let mut token_call = token.call_builder::<SimpleToken>();
+            client
+                .call(&ink_e2e::alice(), &token_call.mint(bob, 100))
+                .submit()
+                .await
+                .expect("mint failed");
+
+            let code_hash = client
+                .upload("token_contract", &ink_e2e::alice())
+                .submit()
+                .await
+                .expect("upload failed")
+                .code_hash;
+            client
+                .call(&ink_e2e::alice(), &token_call.upgrade(code_hash))
+                .submit()
+                .await
+                .expect("upgrade failed");
+
+            let migrated = client.call(&ink_e2e::alice(), &token_call.is_migrated()).dry_run().await?;
+            assert!(!migrated.return_value());
+            client
+                .call(&ink_e2e::alice(), &token_call.migrate())
+                .submit()
+                .await
+                .expect("migrate failed");
+            let balance = client.call(&ink_e2e::alice(), &token_call.balance_of(bob)).dry_run().await?;
+            assert_eq!(balance.return_value(), 100);
             Ok(())
         }
 
-        /// Check if an address is blacklisted
-        #[ink(message)]
-        pub fn is_blacklisted(&self, address: AccountId) -> bool {
-            self.blacklist.get(&address).unwrap_or(false)
+        #[ink_e2e::test]
+        async fn safe_mint_needs_contract_receivers_to_accept<Client: E2EBackend>(
+            mut client: Client,
+        ) -> E2EResult<()> {
+            let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let mut constructor = SimpleTokenRef::new();
+            let token = client
+                .instantiate("token_contract", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("token instantiate failed");
+            let mut token_call = token.call_builder::<SimpleToken>();
+            let mut constructor = TestMockRef::new(true);
+            let accepting = client
+                .instantiate("test_mock", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("mock instantiate failed");
+            let accepting_call = accepting.call_builder::<TestMock>();
+            let mut constructor = TestMockRef::new(false);
+            let rejecting = client
+                .instantiate("test_mock", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("mock instantiate failed");
+
+            client
+                .call(&ink_e2e::alice(), &token_call.safe_mint(bob, 10))
+                .submit()
+                .await
+                .expect("safe_mint to an account failed");
+            client
+                .call(&ink_e2e::alice(), &token_call.safe_mint(accepting.account_id, 20))
+                .submit()
+                .await
+                .expect("safe_mint to an accepting receiver failed");
+            let rejected = client
+                .call(&ink_e2e::alice(), &token_call.safe_mint(rejecting.account_id, 30))
+                .dry_run()
+                .await?;
+            assert_eq!(rejected.return_value(), Err(Error::ReceiverRejected));
+
+            let calls = client.call(&ink_e2e::alice(), &accepting_call.calls()).dry_run().await?;
+            assert_eq!(calls.return_value(), 1);
+            let balance = client.call(&ink_e2e::alice(), &token_call.balance_of(bob)).dry_run().await?;
+            assert_eq!(balance.return_value(), 10);
+            let accepted = client
+                .call(&ink_e2e::alice(), &token_call.balance_of(accepting.account_id))
+                .dry_run()
+                .await?;
+            assert_eq!(accepted.return_value(), 20);
+            let supply = client.call(&ink_e2e::alice(), &token_call.total_supply()).dry_run().await?;
+            assert_eq!(supply.return_value(), 30);
+            Ok(())
         }
 
-        /// Transfer tokens to multiple addresses in a single transaction
-        /// Each recipient will receive the same amount
-        #[ink(message)]
-        pub fn batch_transfer(
-            &mut self,
-            recipients: Vec<AccountId>,
-            amount: Balance,
-        ) -> Result<(), Error> {
-            if self.paused {
-                return Err(Error::ContractPaused);
-            }
-            
-            let caller = self.env().caller();
-            
-            // Check if caller is blacklisted
-            if self.blacklist.get(&caller).unwrap_or(false) {
-                return Err(Error::AddressBlacklisted);
-            }
-            
-            // Check if any recipient is blacklisted
-            for recipient in &recipients {
-                if self.blacklist.get(recipient).unwrap_or(false) {
-                    return Err(Error::AddressBlacklisted);
-                }
-            }
-            
-            if amount == 0 {
-                return Err(Error::InvalidAmount);
-            }
-            
-            let total_amount = amount.checked_mul(recipients.len() as u128)
-                .ok_or(Error::InsufficientBalance)?;
-                
-            let caller_balance = self.balances.get(&caller).unwrap_or(0);
-            if caller_balance < total_amount {
-                return Err(Error::InsufficientBalance);
-            }
-            
-            // Update sender's balance
-            self.balances.insert(&caller, &(caller_balance - total_amount));
-            
-            // Update recipients' balances
-            for recipient in recipients {
-                let recipient_balance = self.balances.get(&recipient).unwrap_or(0);
-                self.balances.insert(&recipient, &(recipient_balance + amount));
-                
-                // Emit transfer event for each recipient
-                self.env().emit_event(Transfer {
-                    from: Some(caller),
-                    to: Some(recipient),
-                    value: amount,
-                });
-            }
-            
+        #[ink_e2e::test]
+        async fn foreign_tokens_are_recovered<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+            let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let mut constructor = SimpleTokenRef::new();
+            let token = client
+                .instantiate("token_contract", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("token instantiate failed");
+            let mut token_call = token.call_builder::<SimpleToken>();
+            let mut constructor = TestMockRef::new(true);
+            let foreign = client
+                .instantiate("test_mock", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("mock instantiate failed");
+            let foreign_call = foreign.call_builder::<TestMock>();
+            let mut constructor = TestMockRef::new(false);
+            let failing = client
+                .instantiate("test_mock", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("mock instantiate failed");
+
+            client
+                .call(
+                    &ink_e2e::alice(),
+                    &token_call.recover_foreign_tokens(foreign.account_id, bob, 25),
+                )
+                .submit()
+                .await
+                .expect("recover_foreign_tokens failed");
+            let last_transfer = client.call(&ink_e2e::alice(), &foreign_call.last_transfer()).dry_run().await?;
+            assert_eq!(last_transfer.return_value(), Some((token.account_id, bob, 25)));
+
+            let failed = client
+                .call(
+                    &ink_e2e::alice(),
+                    &token_call.recover_foreign_tokens(failing.account_id, bob, 25),
+                )
+                .dry_run()
+                .await?;
+            assert_eq!(failed.return_value(), Err(Error::TransferFailed));
             Ok(())
         }
 
-        /// Burn tokens from the caller's account
-        #[ink(message)]
-        pub fn burn(&mut self, amount: Balance) -> Result<(), Error> {
-            if amount == 0 {
-                return Err(Error::InvalidAmount);
-            }
-            
-            let caller = self.env().caller();
-            let current_balance = self.balances.get(&caller).unwrap_or(0);
-            
-            if current_balance < amount {
-                return Err(Error::InsufficientBalance);
-            }
-            
-            self.balances.insert(&caller, &(current_balance - amount));
-            self.total_supply -= amount;
-            
-            self.env().emit_event(Transfer {
-                from: Some(caller),
-                to: None,
-                value: amount,
-            });
-            
+        #[ink_e2e::test]
+        async fn compliance_registry_decides_transfers<Client: E2EBackend>(mut client: Client) -> E2EResult<()> {
+            let alice = ink_e2e::account_id(ink_e2e::AccountKeyring::Alice);
+            let bob = ink_e2e::account_id(ink_e2e::AccountKeyring::Bob);
+            let charlie = ink_e2e::account_id(ink_e2e::AccountKeyring::Charlie);
+            let mut constructor = SimpleTokenRef::new();
+            let token = client
+                .instantiate("token_contract", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("token instantiate failed");
+            let mut token_call = token.call_builder::<SimpleToken>();
+            let mut constructor = TestMockRef::new(true);
+            let registry = client
+                .instantiate("test_mock", &ink_e2e::alice(), &mut constructor)
+                .submit()
+                .await
+                .expect("mock instantiate failed");
+            let mut registry_call = registry.call_builder::<TestMock>();
+
+            client
+                .call(&ink_e2e::alice(), &token_call.mint(alice, 100))
+                .submit()
+                .await
+                .expect("mint failed");
+            client
+                .call(&ink_e2e::alice(), &token_call.add_to_blacklist(bob, None))
+                .submit()
+                .await
+                .expect("add_to_blacklist failed");
+            client
+                .call(&ink_e2e::alice(), &token_call.set_compliance_registry(Some(registry.account_id)))
+                .submit()
+                .await
+                .expect("set_compliance_registry failed");
+            client
+                .call(&ink_e2e::alice(), &registry_call.deny(alice, charlie))
+                .submit()
+                .await
+                .expect("deny failed");
+
+            client
+                .call(&ink_e2e::alice(), &token_call.transfer(bob, 40, Vec::new()))
+                .submit()
+                .await
+                .expect("transfer allowed by the registry failed");
+            let denied = client
+                .call(&ink_e2e::alice(), &token_call.transfer(charlie, 40, Vec::new()))
+                .dry_run()
+                .await?;
+            assert_eq!(
+                denied.return_value(),
+                Err(PSP22Error::Custom(String::from("AddressBlacklisted")))
+            );
+
+            client
+                .call(&ink_e2e::alice(), &token_call.set_compliance_registry(Some(bob)))
+                .submit()
+                .await
+                .expect("set_compliance_registry failed");
+            let failed = client
+                .call(&ink_e2e::alice(), &token_call.can_transfer(alice, bob, 10))
+                .dry_run()
+                .await?;
+            assert_eq!(failed.return_value(), Err(Error::AddressBlacklisted));
+
+            let balance = client.call(&ink_e2e::alice(), &token_call.balance_of(bob)).dry_run().await?;
+            assert_eq!(balance.return_value(), 40);
             Ok(())
         }
     }
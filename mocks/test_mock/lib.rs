@@ -0,0 +1,123 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+pub use self::test_mock::{TestMock, TestMockRef};
+
+/// Stand-in for the contracts the token calls out to, used by the token's end-to-end tests
+/// Deployed with `accept` set to false, every callback is rejected
+#[ink::contract]
+mod test_mock {
+    use ink::prelude::vec::Vec;
+    use ink::prelude::string::String;
+    use ink::storage::Mapping;
+
+    /// First variant of the PSP22 error, enough to answer a PSP22 caller
+    #[derive(scale::Encode, scale::Decode, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum PSP22Error {
+        /// Error not covered by the standard, carrying its name
+        Custom(String),
+    }
+
+    /// The PSP22 transfer message, so the mock can stand in for a foreign token
+    #[ink::trait_definition]
+    pub trait PSP22 {
+        #[ink(message)]
+        fn transfer(&mut self, to: AccountId, value: Balance, data: Vec<u8>) -> Result<(), PSP22Error>;
+    }
+
+    #[ink(storage)]
+    pub struct TestMock {
+        /// Whether callbacks succeed
+        accept: bool,
+        /// Number of callbacks accepted so far
+        calls: u32,
+        /// Arguments of the last accepted transfer hook or foreign token transfer call
+        last_transfer: Option<(AccountId, AccountId, Balance)>,
+        /// Sender and recipient pairs the compliance check refuses
+        denied: Mapping<(AccountId, AccountId), ()>,
+    }
+
+    impl TestMock {
+        #[ink(constructor)]
+        pub fn new(accept: bool) -> Self {
+            Self {
+                accept,
+                calls: 0,
+                last_transfer: None,
+                denied: Mapping::default(),
+            }
+        }
+
+        /// Transfer hook of the token, reverts when not accepting
+        #[ink(message)]
+        pub fn on_token_transfer(&mut self, from: AccountId, to: AccountId, value: Balance) {
+            assert!(self.accept, "transfer hook rejected the transfer");
+            self.calls = self.calls.saturating_add(1);
+            self.last_transfer = Some((from, to, value));
+        }
+
+        /// Receiver callback of `transfer_and_call`, returns its own selector only when accepting
+        #[ink(message)]
+        pub fn on_transfer_received(
+            &mut self,
+            _operator: AccountId,
+            _from: AccountId,
+            _value: Balance,
+            _data: Vec<u8>,
+        ) -> [u8; 4] {
+            if !self.accept {
+                return [0; 4];
+            }
+            self.calls = self.calls.saturating_add(1);
+            ink::selector_bytes!("on_transfer_received")
+        }
+
+        /// Receiver callback of `safe_mint`, returns its own selector only when accepting
+        #[ink(message)]
+        pub fn on_token_received(&mut self, _operator: AccountId, _value: Balance) -> [u8; 4] {
+            if !self.accept {
+                return [0; 4];
+            }
+            self.calls = self.calls.saturating_add(1);
+            ink::selector_bytes!("on_token_received")
+        }
+
+        /// Compliance check of the token, refuses everything when not accepting
+        #[ink(message)]
+        pub fn is_allowed(&self, from: AccountId, to: AccountId) -> bool {
+            self.accept && !self.denied.contains((from, to))
+        }
+
+        /// Make the compliance check refuse transfers from `from` to `to`
+        #[ink(message)]
+        pub fn deny(&mut self, from: AccountId, to: AccountId) {
+            self.denied.insert((from, to), &());
+        }
+
+        /// Get the number of callbacks accepted so far
+        #[ink(message)]
+        pub fn calls(&self) -> u32 {
+            self.calls
+        }
+
+        /// Get the arguments of the last accepted transfer hook or foreign token transfer call
+        #[ink(message)]
+        pub fn last_transfer(&self) -> Option<(AccountId, AccountId, Balance)> {
+            self.last_transfer
+        }
+    }
+
+    impl PSP22 for TestMock {
+        /// Foreign token transfer called by `recover_foreign_tokens`, fails when not accepting
+        /// Records the calling contract as the sender
+        #[ink(message)]
+        fn transfer(&mut self, to: AccountId, value: Balance, _data: Vec<u8>) -> Result<(), PSP22Error> {
+            if !self.accept {
+                return Err(PSP22Error::Custom(String::from("Rejected")));
+            }
+            self.calls = self.calls.saturating_add(1);
+            self.last_transfer = Some((self.env().caller(), to, value));
+            Ok(())
+        }
+    }
+}